@@ -0,0 +1,231 @@
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+#![warn(clippy::cargo)]
+#![warn(clippy::nursery)]
+#![allow(clippy::multiple_crate_versions)]
+//! `#[derive(SerializableEnum)]` for enums whose variants all hold a single value, so they can
+//! be stored in an `EnumMap`.
+//!
+//! Keys are the `snake_case` variant names unless renamed with
+//! `#[serializable_enum(rename = "key")]`. A `<Enum>Kind` enum with the same variants and no
+//! values is generated too, its name can be changed with `#[serializable_enum(kind = "Name")]`
+//! on the enum.
+//!
+//! Two variants can't have the same key:
+//!
+//! ```compile_fail
+//! use deluge_rpc_api::enum_map::SerializableEnum;
+//!
+//! #[derive(SerializableEnum)]
+//! enum Options {
+//!     Label(String),
+//!     #[serializable_enum(rename = "label")]
+//!     LabelId(String),
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr};
+
+#[proc_macro_derive(SerializableEnum, attributes(serializable_enum))]
+pub fn derive_serializable_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Value of `#[serializable_enum(<name> = "...")]` if present in `attrs`.
+fn attribute(attrs: &[syn::Attribute], name: &str) -> syn::Result<Option<LitStr>> {
+    let mut out = None;
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serializable_enum"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(name) {
+                out = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("rename") || meta.path.is_ident("kind") {
+                meta.value()?.parse::<LitStr>().map(|_| ())
+            } else {
+                Err(meta.error("expected `rename` or `kind`"))
+            }
+        })?;
+    }
+    Ok(out)
+}
+
+/// Same word boundaries as `heck`, which `strum` uses: before an uppercase letter that follows
+/// a lowercase one, and before the last letter of an acronym followed by a lowercase one, so
+/// `HTTPAuth` is `http_auth`. Digits stay in the current word.
+fn snake_case(ident: &Ident) -> String {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mode {
+        Boundary,
+        Lowercase,
+        Uppercase,
+    }
+
+    let chars: Vec<char> = ident.to_string().chars().collect();
+    let mut out = String::new();
+    let mut mode = Mode::Boundary;
+    for (i, &c) in chars.iter().enumerate() {
+        out.extend(c.to_lowercase());
+        if c.is_lowercase() {
+            mode = Mode::Lowercase;
+        } else if c.is_uppercase() {
+            mode = Mode::Uppercase;
+        }
+        let boundary = match (chars.get(i + 1), chars.get(i + 2)) {
+            (Some(next), _) if mode == Mode::Lowercase && next.is_uppercase() => true,
+            (Some(next), Some(after)) => {
+                mode == Mode::Uppercase && next.is_uppercase() && after.is_lowercase()
+            }
+            _ => false,
+        };
+        if boundary {
+            out.push('_');
+            mode = Mode::Boundary;
+        }
+    }
+    out
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "SerializableEnum can only be derived for enums",
+        ));
+    };
+    let name = &input.ident;
+    let vis = &input.vis;
+    let kind = attribute(&input.attrs, "kind")?.map_or_else(
+        || Ok(Ident::new(&format!("{name}Kind"), Span::call_site())),
+        |kind| kind.parse::<Ident>(),
+    )?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut variants = Vec::new();
+    let mut keys = Vec::new();
+    for variant in &data.variants {
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "SerializableEnum variants must hold exactly one unnamed value",
+                ))
+            }
+        }
+        let key = attribute(&variant.attrs, "rename")?
+            .map_or_else(|| snake_case(&variant.ident), |key| key.value());
+        if keys.contains(&key) {
+            return Err(syn::Error::new_spanned(variant, "duplicate key"));
+        }
+        keys.push(key);
+        variants.push(&variant.ident);
+    }
+    let count = variants.len();
+    let doc = format!("Variants of [`{name}`] without their values.");
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #vis enum #kind {
+            #(#variants,)*
+        }
+
+        impl #kind {
+            pub const ALL: [Self; #count] = [#(Self::#variants,)*];
+
+            #[must_use]
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #keys,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::deluge_rpc_api::enum_map::EnumKind for #name #ty_generics #where_clause {
+            type Kind = #kind;
+
+            fn kind(&self) -> Self::Kind {
+                match self {
+                    #(Self::#variants(_) => #kind::#variants,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::deluge_rpc_api::enum_map::SerializableEnum for #name #ty_generics #where_clause {
+            type K = &'static str;
+
+            type V = ::deluge_rpc_api::__private::serde_json::Value;
+
+            fn get_key(&self) -> Self::K {
+                ::deluge_rpc_api::enum_map::EnumKind::kind(self).as_str()
+            }
+
+            fn get_value(&self) -> Self::V {
+                match self {
+                    #(Self::#variants(value) => ::deluge_rpc_api::__private::serde_json::json!(value),)*
+                }
+            }
+        }
+
+        impl #impl_generics ::deluge_rpc_api::enum_map::DeserializableEnum for #name #ty_generics #where_clause {
+            fn kind_from_key(key: &str) -> ::core::option::Option<#kind> {
+                match key {
+                    #(#keys => ::core::option::Option::Some(#kind::#variants),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn from_kind_value(
+                kind: #kind,
+                value: ::deluge_rpc_api::__private::serde_json::Value,
+            ) -> ::deluge_rpc_api::__private::serde_json::Result<Self> {
+                match kind {
+                    #(#kind::#variants => ::deluge_rpc_api::__private::serde_json::from_value(value)
+                        .map(Self::#variants),)*
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use proc_macro2::Span;
+    use syn::Ident;
+
+    use super::{expand, snake_case};
+
+    #[test]
+    fn snake_case_words() {
+        let snake_case = |name| snake_case(&Ident::new(name, Span::call_site()));
+        assert_eq!(snake_case("MaxConnections"), "max_connections");
+        assert_eq!(snake_case("HTTPAuth"), "http_auth");
+        assert_eq!(snake_case("DhtURL"), "dht_url");
+        assert_eq!(snake_case("UPnP"), "u_pn_p");
+        assert_eq!(snake_case("V2Hash"), "v2_hash");
+        assert_eq!(snake_case("Ipv6Address"), "ipv6_address");
+        assert_eq!(snake_case("A"), "a");
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let input = syn::parse_quote! {
+            enum Options {
+                Label(String),
+                #[serializable_enum(rename = "label")]
+                LabelId(String),
+            }
+        };
+        let error = expand(&input).map(|_| ()).unwrap_err();
+        assert_eq!(error.to_string(), "duplicate key");
+    }
+}
@@ -0,0 +1,32 @@
+use std::error::Error;
+
+use deluge_rpc_api::enum_map::{EnumKind, EnumMap, SerializableEnum};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, SerializableEnum)]
+#[serializable_enum(kind = "Key")]
+enum Example {
+    MaxSpeed(f64),
+    #[serializable_enum(rename = "label_id")]
+    Label(String),
+    HTTPAuth(bool),
+}
+
+#[test]
+fn derive() -> Result<(), Box<dyn Error>> {
+    let mut map = EnumMap::new();
+    map.insert(Example::Label("tv".to_owned()));
+    assert_eq!(json!(map), json!({"label_id": "tv"}));
+    let map: EnumMap<Example> =
+        serde_json::from_str(r#"{"max_speed": 1.5, "http_auth": true, "other": 1}"#)?;
+    let item = map.iter().next();
+    assert_eq!(item, Some(&Example::MaxSpeed(1.5)));
+    assert_eq!(item.map(EnumKind::kind), Some(Key::MaxSpeed));
+    assert_eq!(map.get(Key::HTTPAuth), Some(&Example::HTTPAuth(true)));
+    assert_eq!(
+        Key::ALL.map(Key::as_str),
+        ["max_speed", "label_id", "http_auth"]
+    );
+    assert_eq!(map.unknown()["other"], json!(1));
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::DelugeApiError;
+
+use super::{DelugeInterface, RequestBuilder};
+
+/// Methods of the `auth` namespace.
+#[derive(Debug, Clone, Copy)]
+pub struct AuthClient<'a> {
+    interface: &'a DelugeInterface,
+}
+
+impl<'a> AuthClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self { interface }
+    }
+
+    fn request(self, method: &str) -> RequestBuilder<'a> {
+        self.interface.request(method)
+    }
+
+    pub async fn change_password(
+        &self,
+        old_password: String,
+        new_password: String,
+    ) -> Result<bool, DelugeApiError> {
+        self.request("auth.change_password")
+            .add_param(&old_password)
+            .add_param(&new_password)
+            .send()
+            .await?
+            .into_result()
+    }
+    pub async fn check_session(&self) -> Result<bool, DelugeApiError> {
+        self.request("auth.check_session")
+            .send()
+            .await?
+            .into_result()
+    }
+    pub async fn delete_session(&self) -> Result<bool, DelugeApiError> {
+        self.request("auth.delete_session")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn login(&self, password: String) -> Result<bool, DelugeApiError> {
+        log::debug!("Logging In");
+        self.request("auth.login")
+            .add_param(&password)
+            .send()
+            .await?
+            .into_result()
+    }
+}
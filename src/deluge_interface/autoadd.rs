@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use crate::{torrent_stuff::WatchDir, DelugeApiError};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the AutoAdd plugin (`autoadd` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct AutoAddClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> AutoAddClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "autoadd"),
+        }
+    }
+
+    /// Watch folders by their id.
+    pub async fn get_watchdirs(&self) -> Result<HashMap<u32, WatchDir>, DelugeApiError> {
+        log::debug!("Getting watch folders");
+        self.plugin
+            .request("get_watchdirs")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Returns the id of the new watch folder, `watchdir.path` must exist in the daemon.
+    pub async fn add(&self, watchdir: &WatchDir) -> Result<u32, DelugeApiError> {
+        log::debug!("Adding watch folder {}", watchdir.path);
+        self.plugin
+            .request("add")
+            .add_param(watchdir)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn remove(&self, watchdir_id: u32) -> Result<(), DelugeApiError> {
+        log::debug!("Removing watch folder {watchdir_id}");
+        self.plugin
+            .request("remove")
+            .add_param(&watchdir_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn enable(&self, watchdir_id: u32) -> Result<(), DelugeApiError> {
+        self.plugin
+            .request("enable_watchdir")
+            .add_param(&watchdir_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn disable(&self, watchdir_id: u32) -> Result<(), DelugeApiError> {
+        self.plugin
+            .request("disable_watchdir")
+            .add_param(&watchdir_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Replaces the options of the watch folder. AutoAdd disables the folder while updating
+    /// it, so `watchdir.enabled` should be set to keep it enabled.
+    pub async fn set_options(
+        &self,
+        watchdir_id: u32,
+        watchdir: &WatchDir,
+    ) -> Result<(), DelugeApiError> {
+        self.plugin
+            .request("set_options")
+            .add_param(&watchdir_id)
+            .add_param(watchdir)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+}
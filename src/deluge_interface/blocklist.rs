@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::{
+    torrent_stuff::{BlocklistConfig, BlocklistState, BlocklistStatus},
+    DelugeApiError,
+};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Blocklist plugin (`blocklist` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct BlocklistClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> BlocklistClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "blocklist"),
+        }
+    }
+
+    pub async fn get_config(&self) -> Result<BlocklistConfig, DelugeApiError> {
+        log::debug!("Getting blocklist config");
+        self.plugin
+            .request("get_config")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn set_config(&self, config: &BlocklistConfig) -> Result<(), DelugeApiError> {
+        log::debug!("Setting blocklist config");
+        self.plugin
+            .request("set_config")
+            .add_param(config)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Downloads and imports the blocklist if it's out of date, or always if `force`.
+    pub async fn check_import(&self, force: bool) -> Result<(), DelugeApiError> {
+        log::debug!("Checking blocklist import");
+        let out = self
+            .plugin
+            .request("check_import")
+            .add_param(&force)
+            .send::<Value>()
+            .await?
+            .into_result();
+        match out {
+            Ok(_) | Err(DelugeApiError::EmptyResult) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn get_status(&self) -> Result<BlocklistStatus, DelugeApiError> {
+        self.plugin
+            .request("get_status")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Polls `get_status` every `interval` until the plugin is idle. Fails if the blocklist
+    /// isn't loaded afterwards (see [`BlocklistStatus::is_loaded`]) or `timeout` elapses.
+    pub async fn wait_for_import(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<BlocklistStatus, DelugeApiError> {
+        let start = Instant::now();
+        loop {
+            let status = self.get_status().await?;
+            log::debug!(
+                "Blocklist {:?} {:.0}%",
+                status.state,
+                status.file_progress * 100.0
+            );
+            if status.state == BlocklistState::Idle {
+                return if status.is_loaded() {
+                    Ok(status)
+                } else {
+                    Err(DelugeApiError::BlocklistNotLoaded)
+                };
+            }
+            if start.elapsed() >= timeout {
+                return Err(DelugeApiError::Timeout);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
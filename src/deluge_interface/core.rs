@@ -0,0 +1,749 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr},
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::{header::HeaderMap, Url};
+use serde_json::Value;
+
+use crate::{
+    enum_map::DeserializableEnum,
+    metainfo::TorrentMetainfo,
+    torrent_stuff::{
+        Account, ConfigKey, ConfigOption, ConfigOptions, CoreConfig, CreatedTorrent, MagnetUri,
+        ProxyConfig, RemotePath, SessionStatus, SessionStatusKey, StatusOptions, Torrent,
+        TorrentId, TorrentOption, TorrentOptions, TorrentTracker,
+    },
+    DelugeApiError, DelugeError,
+};
+
+use super::{DelugeInterface, RequestBuilder};
+
+/// Methods of the `core` namespace.
+#[derive(Debug, Clone, Copy)]
+pub struct CoreClient<'a> {
+    interface: &'a DelugeInterface,
+}
+
+impl<'a> CoreClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self { interface }
+    }
+
+    fn request(self, method: &str) -> RequestBuilder<'a> {
+        self.interface.request(method)
+    }
+
+    pub async fn add_torrent_file_async(
+        &self,
+        filename: &Utf8Path,
+        filedump: &str,
+        options: &TorrentOptions,
+        save_state: Option<bool>,
+    ) -> Result<Option<TorrentId>, DelugeApiError> {
+        log::debug!("Adding Torrent File");
+        let mut builder = self.request("core.add_torrent_file_async");
+        builder
+            .add_param(&filename)
+            .add_param(&filedump)
+            .add_param(options);
+        if let Some(save_state) = save_state {
+            builder.add_param(&save_state);
+        }
+        builder.send().await?.into_result()
+    }
+
+    /// Returns the torrent id and the base64 encoded metadata.
+    pub async fn prefetch_magnet_metadata(
+        &self,
+        magnet_uri: &MagnetUri,
+        timeout: Option<Duration>,
+    ) -> Result<(TorrentId, String), DelugeApiError> {
+        log::debug!("Prefetching Magnet Metadata");
+        let mut builder = self.request("core.prefetch_magnet_metadata");
+        builder.add_param(magnet_uri);
+        if let Some(timeout) = timeout {
+            builder.add_param(&timeout.as_secs());
+        }
+        builder.send().await?.into_result()
+    }
+
+    pub async fn add_torrent_file(
+        &self,
+        filename: &Utf8Path,
+        filedump: &str,
+        options: &TorrentOptions,
+    ) -> Result<Option<TorrentId>, DelugeApiError> {
+        log::debug!("Adding Torrent File");
+        self.request("core.add_torrent_file")
+            .add_param(&filename)
+            .add_param(&filedump)
+            .add_param(options)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Adds a torrent parsed locally, the dump is base64 encoded and the torrent is checked
+    /// against [`CoreClient::get_session_state`] before being uploaded.
+    pub async fn add_torrent_metainfo(
+        &self,
+        metainfo: &TorrentMetainfo,
+        options: &TorrentOptions,
+    ) -> Result<Option<TorrentId>, DelugeApiError> {
+        let id = metainfo.id();
+        if self.get_session_state().await?.contains(&id) {
+            return Err(DelugeError::DuplicateTorrent(id.to_string()).into());
+        }
+        let filename = Utf8PathBuf::from(format!("{}.torrent", metainfo.name()));
+        self.add_torrent_file(&filename, &metainfo.to_base64(), options)
+            .await
+    }
+
+    pub async fn add_torrent_files(
+        &self,
+        torrent_files: &[(Utf8PathBuf, String, TorrentOptions)],
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.add_torrent_files")
+            .add_param(&torrent_files)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn add_torrent_url(
+        &self,
+        url: Url,
+        options: &TorrentOptions,
+        headers: Option<&HeaderMap>,
+    ) -> Result<Option<TorrentId>, DelugeApiError> {
+        log::debug!("Adding torrent from url");
+        let mut builder = self.request("core.add_torrent_url");
+        builder.add_param(&url.as_str()).add_param(options);
+        if let Some(headers) = headers {
+            builder.add_param(
+                &headers
+                    .into_iter()
+                    .map(|(k, v)| {
+                        v.to_str()
+                            .map(|v| (k.to_string(), v.to_string()))
+                            .map_err(|_| DelugeApiError::IncorrectHeaderFormat)
+                    })
+                    .collect::<Result<HashMap<String, String>, DelugeApiError>>()?,
+            );
+        }
+        builder.send().await?.into_result()
+    }
+
+    pub async fn add_torrent_magnet(
+        &self,
+        uri: &MagnetUri,
+        options: &TorrentOptions,
+    ) -> Result<TorrentId, DelugeApiError> {
+        log::debug!("Adding Torrent from magnet");
+        let out = self
+            .request("core.add_torrent_magnet")
+            .add_param(uri)
+            .add_param(&options)
+            .send()
+            .await?
+            .into_result();
+        let out = if let Err(DelugeApiError::Deluge(DelugeError::DuplicateTorrent(id))) = out {
+            id.parse()?
+        } else {
+            out?
+        };
+        Ok(out)
+    }
+
+    pub async fn remove_torrent(
+        &self,
+        torrent_id: &TorrentId,
+        remove_data: bool,
+    ) -> Result<bool, DelugeApiError> {
+        log::debug!("Removing Torrent");
+        self.request("core.remove_torrent")
+            .add_param(torrent_id)
+            .add_param(&remove_data)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    #[allow(unused_variables, clippy::unused_async)] // TODO remove this when implemented
+    pub async fn remove_torrents(
+        &self,
+        torrent_ids: &[TorrentId],
+        remove_data: bool,
+    ) -> Result<(), DelugeApiError> {
+        // Actually has rich error
+        todo!("Needs rich error process on into_result")
+    }
+
+    pub async fn get_session_status(
+        &self,
+        keys: &[SessionStatusKey],
+    ) -> Result<SessionStatus, DelugeApiError> {
+        log::debug!("Getting session status");
+        self.request("core.get_session_status")
+            .add_param(&keys)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn force_reannounce(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        self.request("core.force_reannounce")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn pause_torrent(&self, torrent_id: &TorrentId) -> Result<(), DelugeApiError> {
+        log::debug!("Pausing Torrent");
+        self.request("core.pause_torrent")
+            .add_param(torrent_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn pause_torrents(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        log::debug!("Pausing Torrents");
+        self.request("core.pause_torrents")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn connect_peer(
+        &self,
+        torrent_id: &TorrentId,
+        ip: Ipv4Addr,
+        port: u16,
+    ) -> Result<(), DelugeApiError> {
+        log::debug!("Connecting to Peer");
+        self.request("core.connect_peer")
+            .add_param(torrent_id)
+            .add_param(&ip.to_string())
+            .add_param(&port)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// `dest` is the daemon's path, see [`crate::torrent_stuff::PathMappings::to_remote`].
+    pub async fn move_storage(
+        &self,
+        torrent_ids: &[TorrentId],
+        dest: &RemotePath,
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.move_storage")
+            .add_param(&torrent_ids)
+            .add_param(&dest)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn pause_session(&self) -> Result<(), DelugeApiError> {
+        log::debug!("Pausing Session");
+        self.request("core.pause_session")
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn resume_session(&self) -> Result<(), DelugeApiError> {
+        log::debug!("Resuming Session");
+        self.request("core.resume_session")
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn is_session_paused(&self) -> Result<bool, DelugeApiError> {
+        log::debug!("Checking if session is paused");
+        self.request("core.is_session_paused")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn resume_torrent(&self, torrent_id: &TorrentId) -> Result<(), DelugeApiError> {
+        log::debug!("Resume Torrent");
+        self.request("core.resume_torrent")
+            .add_param(torrent_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn resume_torrents(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        log::debug!("Resuming Torrents");
+        self.request("core.resume_torrents")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn get_torrent_status(
+        &self,
+        torrent_id: &TorrentId,
+        keys: &[String],
+        diff: Option<bool>,
+    ) -> Result<HashMap<String, Value>, DelugeApiError> {
+        log::debug!("Getting torrent status");
+        let mut builder = self.request("core.get_torrent_status");
+        builder.add_param(torrent_id).add_param(&keys);
+        if let Some(diff) = diff {
+            builder.add_param(&diff);
+        }
+        builder.send().await?.into_result()
+    }
+
+    pub async fn get_torrents_status(
+        &self,
+        filter_dict: &HashMap<String, Value>,
+        keys: &[String],
+        diff: Option<bool>,
+    ) -> Result<HashMap<String, Value>, DelugeApiError> {
+        let mut builder = self.request("core.get_torrents_status");
+        builder.add_param(filter_dict).add_param(&keys);
+        if let Some(diff) = diff {
+            builder.add_param(&diff);
+        }
+        builder.send().await?.into_result()
+    }
+
+    /// Current options of each torrent, only the options that are part of a torrent's status
+    /// are returned.
+    pub async fn get_torrent_options(
+        &self,
+        torrent_ids: &[TorrentId],
+    ) -> Result<HashMap<TorrentId, TorrentOptions>, DelugeApiError> {
+        log::debug!("Getting torrent options");
+        self.request("core.get_torrents_status")
+            .add_param(&HashMap::from([("id", torrent_ids)]))
+            .add_param(&TorrentOption::STATUS_KEYS)
+            .send::<HashMap<TorrentId, StatusOptions>>()
+            .await?
+            .into_result()
+            .map(|status| {
+                status
+                    .into_iter()
+                    .map(|(id, options)| (id, options.0))
+                    .collect()
+            })
+    }
+
+    pub async fn get_filter_tree(
+        &self,
+        show_zero_hits: Option<bool>,
+        hide_cat: Option<&[String]>,
+    ) -> Result<HashMap<String, Vec<(String, usize)>>, DelugeApiError> {
+        let mut builder = self.request("core.get_filter_tree");
+        if let Some(hide_cat) = hide_cat {
+            if let Some(szh) = show_zero_hits {
+                builder.add_param(&szh);
+            } else {
+                builder.add_param(&true);
+            }
+            builder.add_param(&hide_cat);
+        }
+        builder.send().await?.into_result()
+    }
+
+    pub async fn get_session_state(&self) -> Result<Vec<TorrentId>, DelugeApiError> {
+        log::debug!("Getting session state");
+
+        self.request("core.get_session_state")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_config(&self) -> Result<CoreConfig, DelugeApiError> {
+        log::debug!("Getting config");
+        self.request("core.get_config").send().await?.into_result()
+    }
+
+    pub async fn get_config_value(&self, key: ConfigKey) -> Result<ConfigOption, DelugeApiError> {
+        log::debug!("Getting config");
+
+        let value = self
+            .request("core.get_config_value")
+            .add_param(&key)
+            .send()
+            .await?
+            .into_result()?;
+        ConfigOption::from_kind_value(key, value).map_err(|source| DelugeApiError::InvalidValue {
+            key: key.as_str().to_owned(),
+            source,
+        })
+    }
+
+    pub async fn get_config_values(
+        &self,
+        keys: &[ConfigKey],
+    ) -> Result<ConfigOptions, DelugeApiError> {
+        let mut values: HashMap<String, Value> = self
+            .request("core.get_config_values")
+            .add_param(&keys)
+            .send()
+            .await?
+            .into_result()?;
+        keys.iter()
+            .filter_map(|&key| {
+                values.remove(key.as_str()).map(|value| {
+                    ConfigOption::from_kind_value(key, value).map_err(|source| {
+                        DelugeApiError::InvalidValue {
+                            key: key.as_str().to_owned(),
+                            source,
+                        }
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Only the keys in `config` are updated.
+    pub async fn set_config(&self, config: &ConfigOptions) -> Result<(), DelugeApiError> {
+        self.request("core.set_config")
+            .add_param(config)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn get_listen_port(&self) -> Result<u16, DelugeApiError> {
+        self.request("core.get_listen_port")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_proxy(&self) -> Result<ProxyConfig, DelugeApiError> {
+        self.request("core.get_proxy").send().await?.into_result()
+    }
+
+    /// Replaces the `proxy` key of the config.
+    pub async fn set_proxy(&self, proxy: &ProxyConfig) -> Result<(), DelugeApiError> {
+        log::debug!("Setting proxy");
+        let mut config = ConfigOptions::new();
+        config.insert(ConfigOption::Proxy(proxy.clone()));
+        self.set_config(&config).await
+    }
+
+    pub async fn get_available_plugins(&self) -> Result<Vec<String>, DelugeApiError> {
+        self.request("core.get_available_plugins")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_enabled_plugins(&self) -> Result<Vec<String>, DelugeApiError> {
+        self.request("core.get_enabled_plugins")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn enable_plugin(&self, plugin: &str) -> Result<bool, DelugeApiError> {
+        self.request("core.enable_plugin")
+            .add_param(&plugin)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn disable_plugin(&self, plugin: &str) -> Result<bool, DelugeApiError> {
+        self.request("core.disable_plugin")
+            .add_param(&plugin)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn force_recheck(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        self.request("core.force_recheck")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn set_torrent_options(
+        &self,
+        torrent_ids: &[TorrentId],
+        options: &TorrentOptions,
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.set_torrent_options")
+            .add_param(&torrent_ids)
+            .add_param(options)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn set_torrent_trackers(
+        &self,
+        torrent_id: &TorrentId,
+        trackers: &TorrentTracker,
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.set_trackers")
+            .add_param(torrent_id)
+            .add_param(trackers)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn get_magnet_uri(
+        &self,
+        torrent_id: &TorrentId,
+    ) -> Result<MagnetUri, DelugeApiError> {
+        log::debug!("Getting Magnet Uri of {torrent_id}");
+        self.request("core.get_magnet_uri")
+            .add_param(torrent_id)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_path_size(&self) -> Result<Option<usize>, DelugeApiError> {
+        log::debug!("Getting Path Size");
+        let path_size = self
+            .request("core.get_path_size")
+            .send()
+            .await?
+            .into_result()?;
+        Ok(match path_size {
+            -1 => None,
+            _ => Some(path_size.try_into()?),
+        })
+    }
+
+    /// The daemon hashes the files at the torrent's path on its own disk.
+    pub async fn create_torrent(
+        &self,
+        torrent: Torrent,
+        add_to_session: bool,
+    ) -> Result<CreatedTorrent, DelugeApiError> {
+        log::debug!("Creating Torrent");
+        let (filename, data) = self
+            .request("core.create_torrent")
+            .add_params(&torrent.into_list(add_to_session))
+            .send()
+            .await?
+            .into_result()?;
+        CreatedTorrent::new(filename, data)
+    }
+
+    /// `filedump` is the content of the plugin's egg, it's sent base64 encoded.
+    pub async fn upload_plugin(
+        &self,
+        filename: Utf8PathBuf,
+        filedump: &[u8],
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.upload_plugin")
+            .add_param(&filename)
+            .add_param(&STANDARD.encode(filedump))
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Uploads the egg at the local `path`, rescans and enables the plugin, and checks it's in
+    /// [`CoreClient::get_enabled_plugins`]. The plugin name is taken from the egg's filename
+    /// (e.g. `Label` for `Label-0.3-py3.8.egg`) and returned.
+    pub async fn install_plugin_egg(&self, path: &Utf8Path) -> Result<String, DelugeApiError> {
+        let filename = path
+            .file_name()
+            .ok_or_else(|| DelugeApiError::InvalidPluginEgg(path.to_owned()))?;
+        let name = filename
+            .strip_suffix(".egg")
+            .and_then(|stem| stem.split('-').next())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| DelugeApiError::InvalidPluginEgg(path.to_owned()))?
+            .replace('_', "-");
+        log::debug!("Installing plugin {name}");
+        let filedump = tokio::fs::read(path).await?;
+        self.upload_plugin(filename.into(), &filedump).await?;
+        self.rescan_plugins().await?;
+        self.enable_plugin(&name).await?;
+        if self.get_enabled_plugins().await?.contains(&name) {
+            Ok(name)
+        } else {
+            Err(DelugeApiError::PluginNotEnabled(name))
+        }
+    }
+
+    pub async fn rescan_plugins(&self) -> Result<(), DelugeApiError> {
+        log::debug!("Rescanning Plugins");
+        self.request("core.rescan_plugins")
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn rename_files(
+        &self,
+        torrent_id: &TorrentId,
+        filenames: &[(usize, Utf8PathBuf)],
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.rename_files")
+            .add_param(torrent_id)
+            .add_param(&filenames)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn rename_folder(
+        &self,
+        torrent_id: &TorrentId,
+        folder: Utf8PathBuf,
+        new_folder: Utf8PathBuf,
+    ) -> Result<(), DelugeApiError> {
+        self.request("core.rename_folder")
+            .add_param(torrent_id)
+            .add_param(&folder)
+            .add_param(&new_folder)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn queue_top(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        self.request("core.queue_top")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn queue_up(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        self.request("core.queue_up")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn queue_down(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        self.request("core.queue_down")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn queue_bottom(&self, torrent_ids: &[TorrentId]) -> Result<(), DelugeApiError> {
+        self.request("core.queue_bottom")
+            .add_param(&torrent_ids)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// `path` and the matches are the daemon's paths, see [`crate::torrent_stuff::PathMappings`].
+    pub async fn glob(&self, path: &RemotePath) -> Result<Vec<RemotePath>, DelugeApiError> {
+        self.request("core.glob")
+            .add_param(path)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn test_listen_port(&self) -> Result<bool, DelugeApiError> {
+        log::debug!("Test Listen Port");
+        self.request("core.test_listen_port")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// `path` is the daemon's path, see [`crate::torrent_stuff::PathMappings::to_remote`]. Defaults to the
+    /// download location.
+    pub async fn get_free_space(&self, path: Option<&RemotePath>) -> Result<usize, DelugeApiError> {
+        let mut builder = self.request("core.get_free_space");
+        if let Some(path) = path {
+            builder.add_param(path);
+        }
+        builder.send().await?.into_result()
+    }
+
+    pub async fn external_ip(&self) -> Result<IpAddr, DelugeApiError> {
+        self.request("core.external_ip").send().await?.into_result()
+    }
+
+    pub async fn get_libtorrent_version(&self) -> Result<String, DelugeApiError> {
+        self.request("core.get_libtorrent_version")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    #[allow(unused_variables, clippy::unused_async)] // TODO remove this when implemented
+    pub async fn get_completion_paths(
+        &self,
+        args: &HashMap<String, Value>,
+    ) -> Result<HashMap<String, Value>, DelugeApiError> {
+        todo!()
+    }
+
+    /// Needs admin authority level
+    pub async fn get_known_accounts(&self) -> Result<Vec<Account>, DelugeApiError> {
+        self.request("core.get_known_accounts")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_auth_levels_mappings(
+        &self,
+    ) -> Result<(HashMap<String, usize>, HashMap<usize, String>), DelugeApiError> {
+        self.request("core.get_known_accounts")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Needs admin authority level
+    pub async fn create_account(&self, account: Account) -> Result<bool, DelugeApiError> {
+        self.request("core.create_account")
+            .add_param(&account.username)
+            .add_param(&account.password)
+            .add_param(&account.authlevel)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Needs admin authority level
+    pub async fn update_account(&self, account: Account) -> Result<bool, DelugeApiError> {
+        self.request("core.update_account")
+            .add_param(&account.username)
+            .add_param(&account.password)
+            .add_param(&account.authlevel)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Needs admin authority level
+    pub async fn remove_account(&self, username: &str) -> Result<bool, DelugeApiError> {
+        self.request("core.remove_account")
+            .add_param(&username)
+            .send()
+            .await?
+            .into_result()
+    }
+}
@@ -0,0 +1,43 @@
+use crate::DelugeApiError;
+
+use super::{DelugeInterface, RequestBuilder};
+
+/// Methods of the `daemon` namespace.
+#[derive(Debug, Clone, Copy)]
+pub struct DaemonClient<'a> {
+    interface: &'a DelugeInterface,
+}
+
+impl<'a> DaemonClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self { interface }
+    }
+
+    fn request(self, method: &str) -> RequestBuilder<'a> {
+        self.interface.request(method)
+    }
+
+    pub async fn shutdown(&self) -> Result<(), DelugeApiError> {
+        log::debug!("Shutting down daemon");
+        self.request("daemon.shutdown")
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn get_method_list(&self) -> Result<Vec<String>, DelugeApiError> {
+        self.request("daemon.get_method_list")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_version(&self) -> Result<String, DelugeApiError> {
+        log::debug!("Getting Version");
+        self.request("daemon.get_version")
+            .send()
+            .await?
+            .into_result()
+    }
+    // pub async fn authorized_call(&self, rpc)
+}
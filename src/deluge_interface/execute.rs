@@ -0,0 +1,92 @@
+use crate::{
+    torrent_stuff::{ExecuteCommand, ExecuteEvent},
+    DelugeApiError,
+};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Execute plugin (`execute` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct ExecuteClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> ExecuteClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "execute"),
+        }
+    }
+
+    pub async fn get_commands(&self) -> Result<Vec<ExecuteCommand>, DelugeApiError> {
+        log::debug!("Getting execute commands");
+        self.plugin
+            .request("get_commands")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn add_command(
+        &self,
+        event: ExecuteEvent,
+        command: &str,
+    ) -> Result<(), DelugeApiError> {
+        log::debug!("Adding execute command");
+        self.plugin
+            .request("add_command")
+            .add_param(&event)
+            .add_param(&command)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Replaces the event and command of the command with id `command.id`.
+    pub async fn save_command(&self, command: &ExecuteCommand) -> Result<(), DelugeApiError> {
+        self.plugin
+            .request("save_command")
+            .add_param(&command.id)
+            .add_param(&command.event)
+            .add_param(&command.command)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn remove_command(&self, command_id: &str) -> Result<(), DelugeApiError> {
+        log::debug!("Removing execute command {command_id}");
+        self.plugin
+            .request("remove_command")
+            .add_param(&command_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Makes the commands of the daemon be exactly `commands`, removing the ones not in it and
+    /// adding the missing ones. Returns whether anything changed.
+    pub async fn reconcile(
+        &self,
+        commands: &[(ExecuteEvent, String)],
+    ) -> Result<bool, DelugeApiError> {
+        let mut missing = commands.to_vec();
+        let mut changed = false;
+        for current in self.get_commands().await? {
+            if let Some(pos) = missing
+                .iter()
+                .position(|(event, command)| *event == current.event && *command == current.command)
+            {
+                missing.swap_remove(pos);
+            } else {
+                self.remove_command(&current.id).await?;
+                changed = true;
+            }
+        }
+        for (event, command) in &missing {
+            self.add_command(*event, command).await?;
+            changed = true;
+        }
+        Ok(changed)
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{torrent_stuff::ExtractorConfig, DelugeApiError};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Extractor plugin (`extractor` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct ExtractorClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> ExtractorClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "extractor"),
+        }
+    }
+
+    pub async fn get_config(&self) -> Result<ExtractorConfig, DelugeApiError> {
+        log::debug!("Getting extractor config");
+        self.plugin
+            .request("get_config")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn set_config(&self, config: &ExtractorConfig) -> Result<(), DelugeApiError> {
+        log::debug!("Setting extractor config");
+        self.plugin
+            .request("set_config")
+            .add_param(config)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+}
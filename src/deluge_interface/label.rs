@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::{
+    torrent_stuff::{LabelOptions, TorrentId},
+    DelugeApiError,
+};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Label plugin (`label` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct LabelClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> LabelClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "label"),
+        }
+    }
+
+    pub async fn get_labels(&self) -> Result<Vec<String>, DelugeApiError> {
+        log::debug!("Getting labels");
+        self.plugin
+            .request("get_labels")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Labels must be lowercase alphanumeric (plus `-` and `_`), Deluge errors otherwise.
+    pub async fn add(&self, label_id: &str) -> Result<(), DelugeApiError> {
+        log::debug!("Adding label {label_id}");
+        self.plugin
+            .request("add")
+            .add_param(&label_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn remove(&self, label_id: &str) -> Result<(), DelugeApiError> {
+        log::debug!("Removing label {label_id}");
+        self.plugin
+            .request("remove")
+            .add_param(&label_id)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// `None` removes the label of the torrent.
+    pub async fn set_torrent(
+        &self,
+        torrent_id: &TorrentId,
+        label_id: Option<&str>,
+    ) -> Result<(), DelugeApiError> {
+        self.plugin
+            .request("set_torrent")
+            .add_param(&torrent_id)
+            .add_param(&label_id.unwrap_or_default())
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn get_options(&self, label_id: &str) -> Result<LabelOptions, DelugeApiError> {
+        self.plugin
+            .request("get_options")
+            .add_param(&label_id)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn set_options(
+        &self,
+        label_id: &str,
+        options: &LabelOptions,
+    ) -> Result<(), DelugeApiError> {
+        self.plugin
+            .request("set_options")
+            .add_param(&label_id)
+            .add_param(options)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Status of the torrents with the label `label_id`, an empty label matches the
+    /// torrents without one.
+    pub async fn get_torrents_status(
+        &self,
+        label_id: &str,
+        keys: &[String],
+    ) -> Result<HashMap<String, Value>, DelugeApiError> {
+        let filter_dict = HashMap::from([("label".to_owned(), json!(label_id))]);
+        self.plugin
+            .interface()
+            .core()
+            .get_torrents_status(&filter_dict, keys, None)
+            .await
+    }
+
+    /// Number of torrents of each label, taken from `core.get_filter_tree`.
+    pub async fn get_filter_counts(
+        &self,
+        show_zero_hits: bool,
+    ) -> Result<Vec<(String, usize)>, DelugeApiError> {
+        let mut tree = self
+            .plugin
+            .interface()
+            .core()
+            .get_filter_tree(Some(show_zero_hits), Some(&[]))
+            .await?;
+        Ok(tree.remove("label").unwrap_or_default())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{torrent_stuff::NotificationsConfig, DelugeApiError};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Notifications plugin (`notifications` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct NotificationsClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> NotificationsClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "notifications"),
+        }
+    }
+
+    pub async fn get_config(&self) -> Result<NotificationsConfig, DelugeApiError> {
+        log::debug!("Getting notifications config");
+        self.plugin
+            .request("get_config")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn set_config(&self, config: &NotificationsConfig) -> Result<(), DelugeApiError> {
+        log::debug!("Setting notifications config");
+        self.plugin
+            .request("set_config")
+            .add_param(config)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Events which can be subscribed to, as `(event name, description)`.
+    pub async fn get_handled_events(&self) -> Result<Vec<(String, String)>, DelugeApiError> {
+        self.plugin
+            .request("get_handled_events")
+            .send()
+            .await?
+            .into_result()
+    }
+}
@@ -0,0 +1,59 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::DelugeApiError;
+
+use super::{DelugeInterface, RequestBuilder};
+
+/// Methods of a plugin's namespace, for plugins without a typed client.
+#[derive(Debug, Clone, Copy)]
+pub struct PluginClient<'a> {
+    interface: &'a DelugeInterface,
+    namespace: &'a str,
+}
+
+impl<'a> PluginClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface, namespace: &'a str) -> Self {
+        Self {
+            interface,
+            namespace,
+        }
+    }
+
+    #[must_use]
+    pub const fn namespace(&self) -> &str {
+        self.namespace
+    }
+
+    pub(super) const fn interface(self) -> &'a DelugeInterface {
+        self.interface
+    }
+
+    pub(super) fn request(self, method: &str) -> RequestBuilder<'a> {
+        self.interface
+            .request(&format!("{}.{method}", self.namespace))
+    }
+
+    /// Calls `<namespace>.<method>` with `params` as positional arguments.
+    pub async fn call<V>(&self, method: &str, params: &[Value]) -> Result<V, DelugeApiError>
+    where
+        V: DeserializeOwned,
+    {
+        log::debug!("Calling {}.{method}", self.namespace);
+        self.request(method)
+            .add_params(params)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Same as [`PluginClient::call`] for methods which return nothing.
+    pub async fn call_empty(&self, method: &str, params: &[Value]) -> Result<(), DelugeApiError> {
+        log::debug!("Calling {}.{method}", self.namespace);
+        self.request(method)
+            .add_params(params)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    torrent_stuff::{ScheduleState, SchedulerConfig},
+    DelugeApiError,
+};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Scheduler plugin (`scheduler` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct SchedulerClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> SchedulerClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "scheduler"),
+        }
+    }
+
+    pub async fn get_config(&self) -> Result<SchedulerConfig, DelugeApiError> {
+        log::debug!("Getting scheduler config");
+        self.plugin
+            .request("get_config")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn set_config(&self, config: &SchedulerConfig) -> Result<(), DelugeApiError> {
+        log::debug!("Setting scheduler config");
+        self.plugin
+            .request("set_config")
+            .add_param(config)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    /// Current state of the schedule.
+    pub async fn get_state(&self) -> Result<ScheduleState, DelugeApiError> {
+        let state: String = self
+            .plugin
+            .request("get_state")
+            .send()
+            .await?
+            .into_result()?;
+        state
+            .parse()
+            .map_err(|source| DelugeApiError::InvalidScheduleState { state, source })
+    }
+}
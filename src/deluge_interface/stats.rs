@@ -0,0 +1,61 @@
+use crate::{
+    torrent_stuff::{Stats, StatsTotals},
+    DelugeApiError,
+};
+
+use super::{DelugeInterface, PluginClient};
+
+/// Methods of the Stats plugin (`stats` namespace).
+#[derive(Debug, Clone, Copy)]
+pub struct StatsClient<'a> {
+    plugin: PluginClient<'a>,
+}
+
+impl<'a> StatsClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self {
+            plugin: PluginClient::new(interface, "stats"),
+        }
+    }
+
+    /// `keys` are series such as `upload_rate`, `download_rate`, `payload_upload_rate`,
+    /// `num_connections` or `dht_nodes`, and `interval` must be one of
+    /// [`StatsClient::get_intervals`], otherwise the plugin returns nothing and this errors
+    /// with [`DelugeApiError::EmptyResult`].
+    pub async fn get_stats(&self, keys: &[String], interval: u64) -> Result<Stats, DelugeApiError> {
+        log::debug!("Getting stats");
+        self.plugin
+            .request("get_stats")
+            .add_param(&keys)
+            .add_param(&interval)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Totals of every session, including the current one.
+    pub async fn get_totals(&self) -> Result<StatsTotals, DelugeApiError> {
+        self.plugin
+            .request("get_totals")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    pub async fn get_session_totals(&self) -> Result<StatsTotals, DelugeApiError> {
+        self.plugin
+            .request("get_session_totals")
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Available intervals in seconds.
+    pub async fn get_intervals(&self) -> Result<Vec<u64>, DelugeApiError> {
+        self.plugin
+            .request("get_intervals")
+            .send()
+            .await?
+            .into_result()
+    }
+}
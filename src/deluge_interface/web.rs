@@ -0,0 +1,349 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::{
+    multipart::{Form, Part},
+    Url,
+};
+
+use crate::{
+    torrent_stuff::{
+        Host, MagnetInfo, MagnetUri, PluginInfo, Plugins, RemotePath, TorrentFile, TorrentId,
+        TorrentInfo, TorrentOption, TorrentOptions, TorrentPriorities, UploadResponse, WebConfig,
+        WebTorrent,
+    },
+    utils::OrFalse,
+    DelugeApiError,
+};
+
+use super::{DelugeInterface, RequestBuilder};
+
+/// Methods of the `web` namespace.
+#[derive(Debug, Clone, Copy)]
+pub struct WebClient<'a> {
+    interface: &'a DelugeInterface,
+}
+
+impl<'a> WebClient<'a> {
+    pub(super) const fn new(interface: &'a DelugeInterface) -> Self {
+        Self { interface }
+    }
+
+    fn request(self, method: &str) -> RequestBuilder<'a> {
+        self.interface.request(method)
+    }
+
+    async fn upload(&self, parts: Vec<Part>) -> Result<Vec<RemotePath>, DelugeApiError> {
+        log::debug!("Uploading {} files", parts.len());
+        let form = parts
+            .into_iter()
+            .fold(Form::new(), |form, part| form.part("file", part));
+        let response: UploadResponse = self
+            .interface
+            .client
+            .post(self.interface.endpoint("upload"))
+            .multipart(form)
+            .send()
+            .await?
+            .json()
+            .await?;
+        log::debug!("Got Upload Response");
+        if response.success {
+            Ok(response.files)
+        } else {
+            Err(DelugeApiError::UploadFailed)
+        }
+    }
+
+    pub async fn connect(&self, host_id: &str) -> Result<Vec<String>, DelugeApiError> {
+        self.request("web.connect")
+            .add_param(&host_id)
+            .send()
+            .await?
+            .into_result()
+    }
+    pub async fn connected(&self) -> Result<(), DelugeApiError> {
+        self.request("web.connected")
+            .send()
+            .await?
+            .into_empty_result()
+    }
+
+    pub async fn disconnect(&self) -> Result<(), DelugeApiError> {
+        log::debug!("Disconnecting");
+        self.request("web.disconnect")
+            .send()
+            .await?
+            .into_empty_result()
+    }
+    // pub async fn update_ui(&self, keys, filter_dict)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn get_torrent_status(&self, torrent_id, keys)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn get_torrent_files(&self, torrent_id)->Result<_,DelugeApiError>{todo!()}
+
+    /// Makes deluge-web download the torrent file, `cookie` is sent as the `Cookie` header
+    /// (e.g. `"uid=1; pass=abc"`). Returns the temporary path of the file in the server.
+    pub async fn download_torrent_from_url(
+        &self,
+        url: &Url,
+        cookie: Option<&str>,
+    ) -> Result<RemotePath, DelugeApiError> {
+        log::debug!("Downloading torrent from url");
+        let mut builder = self.request("web.download_torrent_from_url");
+        builder.add_param(&url.as_str());
+        if let Some(cookie) = cookie {
+            builder.add_param(&cookie);
+        }
+        builder.send().await?.into_result()
+    }
+
+    /// `filename` is a path in the deluge-web server, such as the ones returned by
+    /// [`WebClient::upload_torrent_files`]. Returns `None` if the file isn't a valid torrent.
+    pub async fn get_torrent_info(
+        &self,
+        filename: &RemotePath,
+    ) -> Result<Option<TorrentInfo>, DelugeApiError> {
+        log::debug!("Getting Torrent Info");
+        self.request("web.get_torrent_info")
+            .add_param(&filename)
+            .send::<OrFalse<_>>()
+            .await?
+            .into_result()
+            .map(OrFalse::into_option)
+    }
+
+    /// Returns `None` if Deluge can't parse `uri`.
+    pub async fn get_magnet_info(
+        &self,
+        uri: &MagnetUri,
+    ) -> Result<Option<MagnetInfo>, DelugeApiError> {
+        log::debug!("Getting Magnet Info");
+        self.request("web.get_magnet_info")
+            .add_param(uri)
+            .send::<OrFalse<_>>()
+            .await?
+            .into_result()
+            .map(OrFalse::into_option)
+    }
+
+    /// Returns `(success, torrent_id)` for each torrent, in the same order they were given.
+    pub async fn add_torrents(
+        &self,
+        torrents: &[(RemotePath, TorrentOptions)],
+    ) -> Result<Vec<(bool, Option<TorrentId>)>, DelugeApiError> {
+        log::debug!("Adding Torrents");
+        let torrents = torrents
+            .iter()
+            .map(|(path, options)| WebTorrent { path, options })
+            .collect::<Vec<_>>();
+        self.request("web.add_torrents")
+            .add_param(&torrents)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    /// Returns `None` if the torrent was added but Deluge didn't return its id.
+    async fn add_web_torrent(
+        &self,
+        path: RemotePath,
+        options: TorrentOptions,
+    ) -> Result<Option<TorrentId>, DelugeApiError> {
+        added_torrent(self.add_torrents(&[(path, options)]).await?)
+    }
+
+    async fn upload_files(
+        &self,
+        files: &[&Utf8Path],
+        mime: &str,
+    ) -> Result<Vec<RemotePath>, DelugeApiError> {
+        let mut parts = Vec::with_capacity(files.len());
+        for file in files {
+            let filename = file.file_name().unwrap_or_else(|| file.as_str()).to_owned();
+            parts.push(
+                Part::bytes(tokio::fs::read(file).await?)
+                    .file_name(filename)
+                    .mime_str(mime)?,
+            );
+        }
+        self.upload(parts).await
+    }
+
+    /// Uploads local plugin eggs to deluge-web, returns the temporary paths in the server
+    /// in the same order as `files`.
+    pub async fn upload_plugin_files(
+        &self,
+        files: &[&Utf8Path],
+    ) -> Result<Vec<RemotePath>, DelugeApiError> {
+        self.upload_files(files, "application/octet-stream").await
+    }
+
+    /// Uploads local torrent files to deluge-web, returns the temporary paths in the server
+    /// in the same order as `files`.
+    pub async fn upload_torrent_files(
+        &self,
+        files: &[&Utf8Path],
+    ) -> Result<Vec<RemotePath>, DelugeApiError> {
+        self.upload_files(files, "application/x-bittorrent").await
+    }
+
+    /// Uploads a local torrent file and adds it, skipping the files for which `keep` returns
+    /// `false`. Any [`TorrentOption::FilePriorities`] in `options` is overwritten.
+    /// Fails with [`DelugeApiError::AddFailed`] if Deluge doesn't add it.
+    pub async fn preview_then_add<F>(
+        &self,
+        file: &Utf8Path,
+        mut options: TorrentOptions,
+        keep: F,
+    ) -> Result<Option<TorrentId>, DelugeApiError>
+    where
+        F: Fn(&TorrentFile) -> bool,
+    {
+        let path = self
+            .upload_torrent_files(&[file])
+            .await?
+            .pop()
+            .ok_or(DelugeApiError::UploadFailed)?;
+        let info = self
+            .get_torrent_info(&path)
+            .await?
+            .ok_or(DelugeApiError::InvalidTorrent)?;
+        let priorities = info
+            .files()
+            .iter()
+            .map(|file| {
+                if keep(file) {
+                    TorrentPriorities::Normal
+                } else {
+                    TorrentPriorities::Skip
+                }
+            })
+            .collect();
+        options.insert(TorrentOption::FilePriorities(priorities));
+        self.add_web_torrent(path, options).await
+    }
+
+    /// Downloads the torrent file through deluge-web using `cookie` (see
+    /// [`WebClient::download_torrent_from_url`]) and adds it. Unlike
+    /// [`CoreClient::add_torrent_url`](super::CoreClient::add_torrent_url), the cookie doesn't need to be a valid header map.
+    /// Fails with [`DelugeApiError::AddFailed`] if Deluge doesn't add it.
+    pub async fn add_torrent_from_private_url(
+        &self,
+        url: &Url,
+        cookie: Option<&str>,
+        options: TorrentOptions,
+    ) -> Result<Option<TorrentId>, DelugeApiError> {
+        let path = self.download_torrent_from_url(url, cookie).await?;
+        self.add_web_torrent(path, options).await
+    }
+
+    /// Uploads local torrent files as multipart and adds them through `web.add_torrents`.
+    pub async fn add_local_torrents(
+        &self,
+        torrents: Vec<(Utf8PathBuf, TorrentOptions)>,
+    ) -> Result<Vec<(bool, Option<TorrentId>)>, DelugeApiError> {
+        let (files, options): (Vec<_>, Vec<_>) = torrents.into_iter().unzip();
+        let files = files.iter().map(Utf8PathBuf::as_path).collect::<Vec<_>>();
+        let paths = self.upload_torrent_files(&files).await?;
+        let torrents = paths.into_iter().zip(options).collect::<Vec<_>>();
+        self.add_torrents(&torrents).await
+    }
+
+    pub async fn get_hosts(&self) -> Result<Vec<Host>, DelugeApiError> {
+        self.request("web.get_hosts").send().await?.into_result()
+    }
+    // pub async fn get_host_status(&self, host_id)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn add_host(&self, host, port, username='', password='')->Result<_,DelugeApiError>{todo!()}
+    // pub async fn edit_host(&self, host_id, host, port, username='', password='')->Result<_,DelugeApiError>{todo!()}
+    // pub async fn remove_host(&self, host_id)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn start_daemon(&self, port)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn stop_daemon(&self, host_id)->Result<_,DelugeApiError>{todo!()}
+
+    pub async fn get_plugins(&self) -> Result<Plugins, DelugeApiError> {
+        log::debug!("Getting plugins");
+        self.request("web.get_plugins").send().await?.into_result()
+    }
+
+    pub async fn get_plugin_info(&self, name: &str) -> Result<PluginInfo, DelugeApiError> {
+        log::debug!("Getting info of plugin {name}");
+        self.request("web.get_plugin_info")
+            .add_param(&name)
+            .send()
+            .await?
+            .into_result()
+    }
+
+    // pub async fn get_plugin_resources(&self, name)->Result<_,DelugeApiError>{todo!()}
+
+    /// Installs the plugin egg at `path` in the deluge-web server (see
+    /// [`WebClient::upload_plugin_files`]) as `filename` in both deluge-web and the daemon.
+    pub async fn upload_plugin(
+        &self,
+        filename: &str,
+        path: &RemotePath,
+    ) -> Result<bool, DelugeApiError> {
+        log::debug!("Uploading plugin {filename}");
+        self.request("web.upload_plugin")
+            .add_param(&filename)
+            .add_param(&path)
+            .send()
+            .await?
+            .into_result()
+    }
+    // pub async fn register_event_listener(&self, event)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn deregister_event_listener(&self, event)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn get_events(&self)->Result<_,DelugeApiError>{todo!()}
+    // pub async fn get_languages(&self)->Result<_,DelugeApiError>{todo!()}
+
+    pub async fn get_config(&self) -> Result<WebConfig, DelugeApiError> {
+        log::debug!("Getting web config");
+        self.request("web.get_config").send().await?.into_result()
+    }
+
+    /// Only the fields which are `Some` are updated.
+    pub async fn set_config(&self, config: &WebConfig) -> Result<(), DelugeApiError> {
+        log::debug!("Setting web config");
+        self.request("web.set_config")
+            .add_param(config)
+            .send()
+            .await?
+            .into_empty_result()
+    }
+}
+
+/// Result of adding a single torrent through `web.add_torrents`.
+fn added_torrent(
+    added: Vec<(bool, Option<TorrentId>)>,
+) -> Result<Option<TorrentId>, DelugeApiError> {
+    match added.into_iter().next() {
+        Some((true, id)) => Ok(id),
+        _ => Err(DelugeApiError::AddFailed),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::added_torrent;
+    use crate::DelugeApiError;
+
+    #[test]
+    fn added() -> Result<(), Box<dyn Error>> {
+        let id = "c9e1f2a3b4c5d6e7f8091a2b3c4d5e6f70819203";
+        assert_eq!(
+            added_torrent(serde_json::from_str(&format!(r#"[[true, "{id}"]]"#))?)?,
+            Some(id.parse()?)
+        );
+        assert_eq!(
+            added_torrent(serde_json::from_str("[[true, null]]")?)?,
+            None
+        );
+        assert!(matches!(
+            added_torrent(serde_json::from_str("[[false, null]]")?),
+            Err(DelugeApiError::AddFailed)
+        ));
+        assert!(matches!(
+            added_torrent(Vec::new()),
+            Err(DelugeApiError::AddFailed)
+        ));
+        Ok(())
+    }
+}
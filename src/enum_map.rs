@@ -1,162 +1,375 @@
 use std::{
-    collections::{hash_set, HashSet},
-    hash::{Hash, Hasher},
-    mem::discriminant,
+    collections::{btree_map, BTreeMap, HashMap},
+    hash::Hash,
 };
 
-use serde::{ser::SerializeMap, Serialize};
+#[cfg(feature = "derive")]
+pub use deluge_rpc_api_derive::SerializableEnum;
+use serde::{de, ser::SerializeMap, Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug)]
-pub struct EnumMap<E>(HashSet<EnumWrapper<E>>);
+/// Map holding at most one value per variant of `E`, ordered by [`EnumKind::Kind`] so
+/// iteration and serialization are deterministic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumMap<E: EnumKind> {
+    items: BTreeMap<E::Kind, E>,
+    /// Entries without a variant, kept so they survive a round trip.
+    unknown: BTreeMap<String, Value>,
+}
 
-impl<E> IntoIterator for EnumMap<E> {
+impl<E: EnumKind> IntoIterator for EnumMap<E> {
     type Item = E;
 
     type IntoIter = IntoIter<E>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter().into()
+        IntoIter(self.items.into_values())
     }
 }
-impl<'a, E> IntoIterator for &'a EnumMap<E> {
+impl<'a, E: EnumKind> IntoIterator for &'a EnumMap<E> {
     type Item = &'a E;
 
     type IntoIter = Iter<'a, E>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter().into()
+        Iter(self.items.values())
     }
 }
 
 impl<E> Serialize for EnumMap<E>
 where
-    E: SerializableEnum,
+    E: SerializableEnum + EnumKind,
 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut map = serializer.serialize_map(Some(self.len()))?;
+        let mut map = serializer.serialize_map(Some(self.len() + self.unknown.len()))?;
         for item in self {
             let k = item.get_key();
             let v = item.get_value();
             map.serialize_entry(&k, &v)?;
         }
+        for (k, v) in &self.unknown {
+            map.serialize_entry(k, v)?;
+        }
         map.end()
     }
 }
-impl<E> EnumMap<E> {
-    pub fn new() -> Self {
-        Self(HashSet::new())
+
+impl<'de, E> Deserialize<'de> for EnumMap<E>
+where
+    E: DeserializableEnum,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut map = Self::new();
+        for (key, value) in HashMap::<String, Value>::deserialize(deserializer)? {
+            match E::kind_from_key(&key) {
+                Some(kind) => {
+                    map.insert(
+                        E::from_kind_value(kind, value)
+                            .map_err(|e| de::Error::custom(format!("{key}: {e}")))?,
+                    );
+                }
+                None => {
+                    map.unknown.insert(key, value);
+                }
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl<E: EnumKind> EnumMap<E> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            items: BTreeMap::new(),
+            unknown: BTreeMap::new(),
+        }
     }
+    /// Returns the previous value of the same variant.
     pub fn insert(&mut self, item: E) -> Option<E> {
-        self.0.replace(item.into()).map(|x| x.0)
+        self.items.insert(item.kind(), item)
+    }
+    #[must_use]
+    pub fn get(&self, kind: E::Kind) -> Option<&E> {
+        self.items.get(&kind)
     }
-    pub fn get(&self, item: E) -> Option<&E> {
-        self.0.get(&item.into()).map(|x| &x.0)
+    pub fn get_mut(&mut self, kind: E::Kind) -> Option<&mut E> {
+        self.items.get_mut(&kind)
     }
-    pub fn remove(&mut self, item: E) -> Option<E> {
-        self.0.take(&item.into()).map(|x| x.0)
+    #[must_use]
+    pub fn contains(&self, kind: E::Kind) -> bool {
+        self.items.contains_key(&kind)
+    }
+    pub fn remove(&mut self, kind: E::Kind) -> Option<E> {
+        self.items.remove(&kind)
     }
 
+    /// Number of known entries, see [`EnumMap::unknown`].
+    #[must_use]
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.items.len()
     }
 
-    pub fn iter(&self) -> Iter<E> {
-        self.into_iter()
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
     }
-}
 
-impl<E> Extend<E> for EnumMap<E> {
-    fn extend<T: IntoIterator<Item = E>>(&mut self, iter: T) {
-        iter.into_iter().for_each(|e| {
-            self.insert(e);
-        });
+    /// Deserialized entries whose key has no variant.
+    #[must_use]
+    pub const fn unknown(&self) -> &BTreeMap<String, Value> {
+        &self.unknown
     }
-}
 
-impl<E> Default for EnumMap<E> {
-    fn default() -> Self {
-        Self::new()
+    /// Iterates in [`EnumKind::Kind`] order.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, E> {
+        self.into_iter()
     }
-}
-
-struct EnumWrapper<E>(E);
 
-impl<E> EnumWrapper<E> {
-    // //! Make const when const_precise_live_drops reaches stable
-    #[allow(clippy::missing_const_for_fn)]
-    pub fn into_internal(self) -> E {
-        self.0
+    /// Inserts every entry of `other`, replacing the ones of the same variant or key.
+    pub fn merge(&mut self, other: Self) {
+        self.items.extend(other.items);
+        self.unknown.extend(other.unknown);
     }
-    pub const fn get(&self) -> &E {
-        &self.0
+
+    /// [`EnumMap::merge`] that takes and returns `self`, e.g. to put user options over
+    /// defaults.
+    #[must_use]
+    pub fn overlay(mut self, other: Self) -> Self {
+        self.merge(other);
+        self
     }
-}
 
-impl<E> std::fmt::Debug for EnumWrapper<E>
-where
-    E: std::fmt::Debug,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", &self.0)
+    /// Entries of `other` that are missing from `self` or have another value, i.e. what has to
+    /// be set to go from `self` to `other`. Entries only in `self` can't be unset, so they're
+    /// ignored.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Self
+    where
+        E: Clone + PartialEq,
+    {
+        Self {
+            items: other
+                .items
+                .iter()
+                .filter(|(kind, item)| self.items.get(kind) != Some(item))
+                .map(|(kind, item)| (*kind, item.clone()))
+                .collect(),
+            unknown: other
+                .unknown
+                .iter()
+                .filter(|(key, value)| self.unknown.get(*key) != Some(value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
     }
 }
-impl<E> Hash for EnumWrapper<E> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        discriminant(&self.0).hash(state);
+
+impl<E: EnumKind> Extend<E> for EnumMap<E> {
+    fn extend<T: IntoIterator<Item = E>>(&mut self, iter: T) {
+        for item in iter {
+            self.insert(item);
+        }
     }
 }
-impl<E> PartialEq for EnumWrapper<E> {
-    fn eq(&self, other: &Self) -> bool {
-        discriminant(&self.0) == discriminant(&other.0)
+
+impl<E: EnumKind> FromIterator<E> for EnumMap<E> {
+    fn from_iter<T: IntoIterator<Item = E>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
     }
 }
-impl<E> Eq for EnumWrapper<E> {}
 
-impl<E> From<E> for EnumWrapper<E> {
-    fn from(internal: E) -> Self {
-        Self(internal)
+impl<E: EnumKind> Default for EnumMap<E> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-pub struct IntoIter<E>(hash_set::IntoIter<EnumWrapper<E>>);
-impl<E> Iterator for IntoIter<E> {
+pub struct IntoIter<E: EnumKind>(btree_map::IntoValues<E::Kind, E>);
+impl<E: EnumKind> Iterator for IntoIter<E> {
     type Item = E;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(EnumWrapper::into_internal)
+        self.0.next()
     }
-}
 
-impl<E> From<hash_set::IntoIter<EnumWrapper<E>>> for IntoIter<E> {
-    fn from(set: hash_set::IntoIter<EnumWrapper<E>>) -> Self {
-        Self(set)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
-pub struct Iter<'a, E>(hash_set::Iter<'a, EnumWrapper<E>>);
-impl<'a, E> Iterator for Iter<'a, E> {
+pub struct Iter<'a, E: EnumKind>(btree_map::Values<'a, E::Kind, E>);
+impl<'a, E: EnumKind> Iterator for Iter<'a, E> {
     type Item = &'a E;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(EnumWrapper::get)
+        self.0.next()
     }
-}
 
-impl<'a, E> From<hash_set::Iter<'a, EnumWrapper<E>>> for Iter<'a, E>
-where
-    E: 'a,
-{
-    fn from(set: hash_set::Iter<'a, EnumWrapper<E>>) -> Self {
-        Self(set)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
+/// Declares an enum whose variants all hold a single value with its `Kind` and the traits
+/// needed by [`EnumMap`], the same as `#[derive(SerializableEnum)]` does, so the crate's own
+/// enums don't need the `derive` feature.
+macro_rules! serializable_enum {
+    (
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident: $kind:ident {
+            $($(#[$variant_attr:meta])* $variant:ident($ty:ty) => $key:ident,)*
+        }
+    ) => {
+        $(#[$attr])*
+        $vis enum $name {
+            $($(#[$variant_attr])* $variant($ty),)*
+        }
+
+        #[doc = concat!("Variants of [`", stringify!($name), "`] without their values.")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        $vis enum $kind {
+            $($variant,)*
+        }
+
+        impl $kind {
+            pub const ALL: [Self; [$(stringify!($variant)),*].len()] = [$(Self::$variant,)*];
+
+            #[must_use]
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($key),)*
+                }
+            }
+        }
+
+        impl $crate::enum_map::EnumKind for $name {
+            type Kind = $kind;
+
+            fn kind(&self) -> Self::Kind {
+                match self {
+                    $(Self::$variant(_) => $kind::$variant,)*
+                }
+            }
+        }
+
+        impl $crate::enum_map::SerializableEnum for $name {
+            type K = &'static str;
+
+            type V = serde_json::Value;
+
+            fn get_key(&self) -> Self::K {
+                $crate::enum_map::EnumKind::kind(self).as_str()
+            }
+
+            fn get_value(&self) -> Self::V {
+                match self {
+                    $(Self::$variant(value) => serde_json::json!(value),)*
+                }
+            }
+        }
+
+        impl $crate::enum_map::DeserializableEnum for $name {
+            fn kind_from_key(key: &str) -> Option<$kind> {
+                match key {
+                    $(stringify!($key) => Some($kind::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn from_kind_value(kind: $kind, value: serde_json::Value) -> serde_json::Result<Self> {
+                match kind {
+                    $($kind::$variant => serde_json::from_value(value).map(Self::$variant),)*
+                }
+            }
+        }
+    };
+}
+pub(crate) use serializable_enum;
+
 pub trait SerializableEnum {
     type K: Serialize;
     type V: Serialize;
     fn get_key(&self) -> Self::K;
     fn get_value(&self) -> Self::V;
 }
+
+/// Discriminant of an enum whose variants hold values, used as the key of an [`EnumMap`].
+pub trait EnumKind {
+    type Kind: Copy + Eq + Hash + Ord;
+    fn kind(&self) -> Self::Kind;
+}
+
+/// Inverse of [`SerializableEnum`].
+pub trait DeserializableEnum: EnumKind + Sized {
+    /// Returns `None` if there's no variant for `key`.
+    fn kind_from_key(key: &str) -> Option<Self::Kind>;
+    fn from_kind_value(kind: Self::Kind, value: Value) -> serde_json::Result<Self>;
+
+    /// Returns `None` if there's no variant for `key`.
+    #[must_use]
+    fn from_key_value(key: &str, value: Value) -> Option<serde_json::Result<Self>> {
+        Self::kind_from_key(key).map(|kind| Self::from_kind_value(kind, value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::{EnumKind, EnumMap};
+
+    serializable_enum! {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Example: Key {
+            MaxSpeed(f64) => max_speed,
+            Label(String) => label_id,
+        }
+    }
+
+    #[test]
+    fn declared_enum() -> Result<(), Box<dyn Error>> {
+        let mut map = EnumMap::new();
+        map.insert(Example::Label("tv".to_owned()));
+        assert_eq!(json!(map), json!({"label_id": "tv"}));
+        let map: EnumMap<Example> = serde_json::from_str(r#"{"max_speed": 1.5, "other": 1}"#)?;
+        let item = map.iter().next();
+        assert_eq!(item, Some(&Example::MaxSpeed(1.5)));
+        assert_eq!(item.map(EnumKind::kind), Some(Key::MaxSpeed));
+        assert_eq!(Key::ALL.map(Key::as_str), ["max_speed", "label_id"]);
+        assert_eq!(map.unknown()["other"], json!(1));
+        Ok(())
+    }
+
+    #[test]
+    fn merge_diff() -> Result<(), Box<dyn Error>> {
+        let old: EnumMap<_> = [Example::Label("tv".to_owned()), Example::MaxSpeed(1.0)]
+            .into_iter()
+            .collect();
+        let new = EnumMap::from_iter([Example::MaxSpeed(2.0)]);
+        assert_eq!(
+            old.diff(&new).into_iter().collect::<Vec<_>>(),
+            [Example::MaxSpeed(2.0)]
+        );
+        assert!(new.diff(&new).is_empty());
+        let merged = old.clone().overlay(new);
+        assert_eq!(merged.get(Key::MaxSpeed), Some(&Example::MaxSpeed(2.0)));
+        assert_eq!(merged.get(Key::Label), old.get(Key::Label));
+        assert_eq!(
+            serde_json::to_string(&merged)?,
+            r#"{"max_speed":2.0,"label_id":"tv"}"#
+        );
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use lazy_static::lazy_static;
+use std::sync::LazyLock;
+
 use regex::{Regex, RegexSet};
 use thiserror::Error;
 
@@ -16,8 +17,67 @@ pub enum DelugeApiError {
     Deluge(#[from] DelugeError),
     #[error(transparent)]
     TryInto(#[from] std::num::TryFromIntError),
+    #[error("Invalid value for {key}: {source}")]
+    InvalidValue {
+        key: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{start}..{end} isn't a range of hours of a day")]
+    InvalidHours { start: u8, end: u8 },
+    #[error("Invalid scheduler state {state}: {source}")]
+    InvalidScheduleState {
+        state: String,
+        #[source]
+        source: strum::ParseError,
+    },
     #[error("Header values are not ASCII complaint")]
     IncorrectHeaderFormat,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Upload to deluge-web failed")]
+    UploadFailed,
+    #[error("Deluge didn't add the torrent")]
+    AddFailed,
+    #[error("Deluge couldn't read the torrent file")]
+    InvalidTorrent,
+    #[error("Timed out")]
+    Timeout,
+    #[error("Blocklist isn't loaded")]
+    BlocklistNotLoaded,
+    #[error("{0} isn't a plugin egg")]
+    InvalidPluginEgg(camino::Utf8PathBuf),
+    #[error("Plugin {0} isn't enabled")]
+    PluginNotEnabled(String),
+    #[error("{0} isn't a valid info hash")]
+    InvalidInfoHash(String),
+    #[error("{0} isn't a valid magnet uri")]
+    InvalidMagnetUri(String),
+    #[error(transparent)]
+    InvalidTorrentOption(#[from] InvalidTorrentOption),
+    #[error(transparent)]
+    Base64(#[from] base64::DecodeError),
+    #[error(transparent)]
+    Metainfo(#[from] crate::metainfo::MetainfoError),
+}
+
+/// Contradiction found by [`crate::torrent_stuff::TorrentOptions::validate`].
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum InvalidTorrentOption {
+    #[error("move_completed is enabled without a move_completed_path")]
+    MoveCompletedWithoutPath,
+    #[error("stop_ratio {0} is negative")]
+    NegativeStopRatio(f64),
+    #[error("remove_at_ratio is enabled but stop_at_ratio is disabled")]
+    RemoveAtRatioWithoutStop,
+    #[error("{0} is below -1")]
+    InvalidLimit(String),
+    #[error("name is empty")]
+    EmptyName,
+    #[error("file_priorities has {found} entries but the torrent has {expected} files")]
+    FilePrioritiesLength { expected: usize, found: usize },
+    #[error("mapped_files has no file with index {0}")]
+    MappedFileIndex(i32),
 }
 
 #[derive(Error, Debug)]
@@ -35,13 +95,9 @@ impl From<ErrorValue> for DelugeError {
     }
 }
 const N: usize = 1;
-const ERROR_REGEX: [&str; N] = [r#"Torrent already in session \((?P<id>[[:alnum:]]+)\)."#];
-lazy_static! {
-    static ref RE: RegexSet = RegexSet::new(&ERROR_REGEX).unwrap();
-}
-lazy_static! {
-    static ref RES: [Regex; N] = ERROR_REGEX.map(|x| Regex::new(x).unwrap());
-}
+const ERROR_REGEX: [&str; N] = [r"Torrent already in session \((?P<id>[[:alnum:]]+)\)."];
+static RE: LazyLock<RegexSet> = LazyLock::new(|| RegexSet::new(ERROR_REGEX).unwrap());
+static RES: LazyLock<[Regex; N]> = LazyLock::new(|| ERROR_REGEX.map(|x| Regex::new(x).unwrap()));
 impl From<String> for DelugeError {
     fn from(val: String) -> Self {
         let matches = RE.matches(&val);
@@ -5,11 +5,21 @@
 // #![warn(missing_docs)] //! Uncomment this after everything is at least functional.
 
 #![allow(clippy::module_name_repetitions)]
+#![allow(clippy::multiple_crate_versions)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::missing_panics_doc)] //! Remove when everything is functional
+
 pub mod deluge_interface;
+pub mod metainfo;
 pub mod torrent_stuff;
-pub use error::{DelugeApiError, DelugeError};
+pub use error::{DelugeApiError, DelugeError, InvalidTorrentOption};
 mod error;
-mod enum_map;
+pub mod enum_map;
 mod utils;
+
+/// Used by the code generated by `#[derive(SerializableEnum)]`.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+}
@@ -0,0 +1,463 @@
+//! Local parsing of `.torrent` files.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest::Url;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::torrent_stuff::{InfoHash, MagnetUri, TorrentId};
+
+mod bencode;
+mod create;
+pub use bencode::*;
+pub use create::*;
+
+#[derive(Error, Debug)]
+pub enum MetainfoError {
+    #[error("Unexpected end of data")]
+    UnexpectedEof,
+    #[error("Unexpected byte {0:#04x} at {1}")]
+    UnexpectedByte(u8, usize),
+    #[error("Invalid integer at {0}")]
+    InvalidInteger(usize),
+    #[error("Trailing data at {0}")]
+    TrailingData(usize),
+    #[error("Bencode nested too deep")]
+    TooDeep,
+    #[error("Missing field {0}")]
+    MissingField(&'static str),
+    #[error("Invalid field {0}")]
+    InvalidField(&'static str),
+}
+
+/// File of a torrent, `path` includes the torrent's name for multi-file torrents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetainfoFile {
+    pub path: Utf8PathBuf,
+    pub length: u64,
+}
+
+/// Info hashes of a torrent, a torrent always has at least one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InfoHashes {
+    V1(InfoHash),
+    V2(InfoHash),
+    Hybrid { v1: InfoHash, v2: InfoHash },
+}
+
+/// Parsed `.torrent` file (BEP 3, and BEP 52 for v2 and hybrid torrents).
+///
+/// The fields are read-only so they always match the bytes sent by
+/// [`TorrentMetainfo::to_base64`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TorrentMetainfo {
+    raw: Vec<u8>,
+    info_hashes: InfoHashes,
+    name: String,
+    piece_length: u64,
+    files: Vec<MetainfoFile>,
+    trackers: Vec<Vec<Url>>,
+    private: bool,
+    web_seeds: Vec<Url>,
+    comment: Option<String>,
+    created_by: Option<String>,
+    creation_date: Option<i64>,
+}
+
+impl TorrentMetainfo {
+    pub fn from_bytes(data: &[u8]) -> Result<Self, MetainfoError> {
+        let (root, info_range) = bencode::decode_with_info(data)?;
+        let info_range = info_range.ok_or(MetainfoError::MissingField("info"))?;
+        let info_bytes = &data[info_range];
+        let info = root
+            .get("info")
+            .ok_or(MetainfoError::MissingField("info"))?;
+        if info.as_dict().is_none() {
+            return Err(MetainfoError::InvalidField("info"));
+        }
+
+        let name = info
+            .get("name.utf-8")
+            .or_else(|| info.get("name"))
+            .ok_or(MetainfoError::MissingField("name"))
+            .and_then(|name| utf8(name, "name"))?;
+        let piece_length = info
+            .get("piece length")
+            .ok_or(MetainfoError::MissingField("piece length"))?
+            .as_int()
+            .and_then(|length| u64::try_from(length).ok())
+            .filter(|&length| length > 0)
+            .ok_or(MetainfoError::InvalidField("piece length"))?;
+
+        let is_v2 = info.get("meta version").and_then(Bencode::as_int) == Some(2);
+        let is_v1 = info.get("pieces").is_some();
+        if !is_v1 && !is_v2 {
+            return Err(MetainfoError::MissingField("pieces"));
+        }
+        let files = if is_v1 {
+            v1_files(info, &name)?
+        } else {
+            let tree = info
+                .get("file tree")
+                .ok_or(MetainfoError::MissingField("file tree"))?;
+            // Single file torrents have the file at the root of the tree, others are in `name`
+            let is_single_file = tree
+                .as_dict()
+                .filter(|tree| tree.len() == 1)
+                .and_then(|tree| tree.values().next())
+                .is_some_and(|node| node.get("").is_some());
+            let mut root = Utf8PathBuf::new();
+            if !is_single_file {
+                push_component(&mut root, &name)?;
+            }
+            let mut files = Vec::new();
+            v2_files(tree, root, &mut files)?;
+            files
+        };
+
+        let trackers = root
+            .get("announce-list")
+            .and_then(Bencode::as_list)
+            .map_or_else(
+                || {
+                    root.get("announce")
+                        .and_then(parse_url)
+                        .map(|url| vec![vec![url]])
+                        .unwrap_or_default()
+                },
+                |tiers| {
+                    tiers
+                        .iter()
+                        .filter_map(Bencode::as_list)
+                        .map(|tier| tier.iter().filter_map(parse_url).collect::<Vec<_>>())
+                        .filter(|tier| !tier.is_empty())
+                        .collect()
+                },
+            );
+        let web_seeds = match root.get("url-list") {
+            Some(Bencode::List(urls)) => urls.iter().filter_map(parse_url).collect(),
+            Some(url) => parse_url(url).into_iter().collect(),
+            None => Vec::new(),
+        };
+
+        let v1 = || InfoHash::V1(Sha1::digest(info_bytes).into());
+        let v2 = || InfoHash::V2(Sha256::digest(info_bytes).into());
+        let info_hashes = match (is_v1, is_v2) {
+            (true, true) => InfoHashes::Hybrid { v1: v1(), v2: v2() },
+            (true, false) => InfoHashes::V1(v1()),
+            _ => InfoHashes::V2(v2()),
+        };
+
+        Ok(Self {
+            info_hashes,
+            name,
+            piece_length,
+            files,
+            trackers,
+            private: info.get("private").and_then(Bencode::as_int) == Some(1),
+            web_seeds,
+            comment: root
+                .get("comment.utf-8")
+                .or_else(|| root.get("comment"))
+                .and_then(Bencode::as_str)
+                .map(ToOwned::to_owned),
+            created_by: root
+                .get("created by")
+                .and_then(Bencode::as_str)
+                .map(ToOwned::to_owned),
+            creation_date: root.get("creation date").and_then(Bencode::as_int),
+            raw: data.to_vec(),
+        })
+    }
+
+    pub fn read(path: &Utf8Path) -> Result<Self, crate::DelugeApiError> {
+        Ok(Self::from_bytes(&std::fs::read(path)?)?)
+    }
+
+    /// Torrent id Deluge will use: the v1 info hash, or the truncated v2 info hash for v2-only
+    /// torrents.
+    #[must_use]
+    pub fn id(&self) -> TorrentId {
+        match self.info_hashes {
+            InfoHashes::V1(hash) | InfoHashes::V2(hash) | InfoHashes::Hybrid { v1: hash, .. } => {
+                hash.to_torrent_id()
+            }
+        }
+    }
+
+    /// SHA-1 of the info dictionary, `None` for v2-only torrents.
+    #[must_use]
+    pub const fn info_hash_v1(&self) -> Option<InfoHash> {
+        match self.info_hashes {
+            InfoHashes::V1(hash) | InfoHashes::Hybrid { v1: hash, .. } => Some(hash),
+            InfoHashes::V2(_) => None,
+        }
+    }
+
+    /// SHA-256 of the info dictionary, `None` for v1-only torrents.
+    #[must_use]
+    pub const fn info_hash_v2(&self) -> Option<InfoHash> {
+        match self.info_hashes {
+            InfoHashes::V2(hash) | InfoHashes::Hybrid { v2: hash, .. } => Some(hash),
+            InfoHashes::V1(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub const fn piece_length(&self) -> u64 {
+        self.piece_length
+    }
+
+    /// Padding files of hybrid torrents are left out.
+    #[must_use]
+    pub fn files(&self) -> &[MetainfoFile] {
+        &self.files
+    }
+
+    /// Tracker tiers, from `announce-list` or else `announce`.
+    #[must_use]
+    pub fn trackers(&self) -> &[Vec<Url>] {
+        &self.trackers
+    }
+
+    #[must_use]
+    pub const fn is_private(&self) -> bool {
+        self.private
+    }
+
+    #[must_use]
+    pub fn web_seeds(&self) -> &[Url] {
+        &self.web_seeds
+    }
+
+    #[must_use]
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    #[must_use]
+    pub fn created_by(&self) -> Option<&str> {
+        self.created_by.as_deref()
+    }
+
+    #[must_use]
+    pub const fn creation_date(&self) -> Option<i64> {
+        self.creation_date
+    }
+
+    /// Magnet link with both hashes of hybrid torrents, the name, size, trackers and web seeds.
+    #[must_use]
+    pub fn magnet_uri(&self) -> MagnetUri {
+        MagnetUri {
+            info_hashes: self
+                .info_hash_v1()
+                .into_iter()
+                .chain(self.info_hash_v2())
+                .collect(),
+            display_name: Some(self.name.clone()),
+            trackers: self.trackers.iter().flatten().cloned().collect(),
+            web_seeds: self.web_seeds.clone(),
+            length: Some(self.total_size()),
+            other: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_hybrid(&self) -> bool {
+        matches!(self.info_hashes, InfoHashes::Hybrid { .. })
+    }
+
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.length).sum()
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Encoding expected by `core.add_torrent_file`.
+    #[must_use]
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(&self.raw)
+    }
+}
+
+fn utf8(value: &Bencode, field: &'static str) -> Result<String, MetainfoError> {
+    value
+        .as_bytes()
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .ok_or(MetainfoError::InvalidField(field))
+}
+
+fn parse_url(value: &Bencode) -> Option<Url> {
+    value.as_str().and_then(|url| Url::parse(url).ok())
+}
+
+fn length(value: &Bencode) -> Result<u64, MetainfoError> {
+    value
+        .get("length")
+        .and_then(Bencode::as_int)
+        .and_then(|length| u64::try_from(length).ok())
+        .ok_or(MetainfoError::InvalidField("length"))
+}
+
+/// Rejects components that would escape the download directory.
+fn push_component(path: &mut Utf8PathBuf, component: &str) -> Result<(), MetainfoError> {
+    if component.is_empty()
+        || component == "."
+        || component == ".."
+        || component.contains(['/', '\\'])
+    {
+        return Err(MetainfoError::InvalidField("path"));
+    }
+    path.push(component);
+    Ok(())
+}
+
+fn v1_files(info: &Bencode, name: &str) -> Result<Vec<MetainfoFile>, MetainfoError> {
+    let mut root = Utf8PathBuf::new();
+    push_component(&mut root, name)?;
+    let Some(files) = info.get("files") else {
+        return Ok(vec![MetainfoFile {
+            path: root,
+            length: length(info)?,
+        }]);
+    };
+    files
+        .as_list()
+        .ok_or(MetainfoError::InvalidField("files"))?
+        .iter()
+        .filter(|file| {
+            file.get("attr")
+                .and_then(Bencode::as_bytes)
+                .is_none_or(|attr| !attr.contains(&b'p'))
+        })
+        .map(|file| {
+            let components = file
+                .get("path.utf-8")
+                .or_else(|| file.get("path"))
+                .and_then(Bencode::as_list)
+                .ok_or(MetainfoError::InvalidField("path"))?;
+            let mut path = root.clone();
+            for component in components {
+                push_component(&mut path, &utf8(component, "path")?)?;
+            }
+            Ok(MetainfoFile {
+                path,
+                length: length(file)?,
+            })
+        })
+        .collect()
+}
+
+fn v2_files(
+    node: &Bencode,
+    path: Utf8PathBuf,
+    files: &mut Vec<MetainfoFile>,
+) -> Result<(), MetainfoError> {
+    let node = node
+        .as_dict()
+        .ok_or(MetainfoError::InvalidField("file tree"))?;
+    if let Some(file) = node.get(&b""[..]) {
+        files.push(MetainfoFile {
+            path,
+            length: length(file)?,
+        });
+        return Ok(());
+    }
+    for (component, child) in node {
+        let mut path = path.clone();
+        push_component(&mut path, &String::from_utf8_lossy(component))?;
+        v2_files(child, path, files)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MetainfoError, TorrentMetainfo};
+
+    #[test]
+    fn single_file_v1() -> Result<(), MetainfoError> {
+        let data = [
+            &b"d8:announce31:http://tracker.example/announce13:announce-listll31:http://tracker.example/announceel19:udp://t2.example:80ee7:comment2:hi4:infod6:lengthi5e4:name5:a.txt12:piece lengthi16384e6:pieces20:"[..],
+            &[0; 20],
+            b"7:privatei1ee8:url-list20:http://seed.example/e",
+        ]
+        .concat();
+        let metainfo = TorrentMetainfo::from_bytes(&data)?;
+        assert_eq!(
+            metainfo.id().to_string(),
+            "ee0101264639007cb9b145601189ef51516fae9b"
+        );
+        assert_eq!(metainfo.info_hash_v2(), None);
+        assert_eq!(metainfo.name(), "a.txt");
+        assert_eq!(metainfo.total_size(), 5);
+        assert_eq!(metainfo.trackers().len(), 2);
+        assert_eq!(metainfo.trackers()[1][0].as_str(), "udp://t2.example:80");
+        assert!(metainfo.is_private());
+        assert_eq!(metainfo.web_seeds()[0].as_str(), "http://seed.example/");
+        assert_eq!(metainfo.comment(), Some("hi"));
+        assert_eq!(metainfo.as_bytes(), data);
+        Ok(())
+    }
+
+    #[test]
+    fn hybrid() -> Result<(), MetainfoError> {
+        let data = [
+            &b"d4:infod9:file treed3:subd1:yd0:d6:lengthi7e11:pieces root32:"[..],
+            &[0x22; 32],
+            b"eee1:xd0:d6:lengthi3e11:pieces root32:",
+            &[0x11; 32],
+            b"eee5:filesld6:lengthi3e4:pathl1:xeed4:attr1:p6:lengthi16381e4:pathl4:.pad5:16381eed6:lengthi7e4:pathl3:sub1:yeee12:meta versioni2e4:name3:dir12:piece lengthi16384e6:pieces40:",
+            &[0; 40],
+            b"e12:piece layersdee",
+        ]
+        .concat();
+        let metainfo = TorrentMetainfo::from_bytes(&data)?;
+        assert!(metainfo.is_hybrid());
+        assert_eq!(
+            metainfo.id().to_string(),
+            "21abe970ab69d83615aa653e28c8cf5bc4e26bff"
+        );
+        assert_eq!(
+            metainfo
+                .info_hash_v2()
+                .map(|hash| hash.to_string())
+                .as_deref(),
+            Some("35aefd8e55c70a58c7c1257518d80b1586c099b010d27a98140768f5372aaed4")
+        );
+        assert_eq!(metainfo.magnet_uri().info_hashes.len(), 2);
+        let paths: Vec<_> = metainfo
+            .files()
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(paths, ["dir/x", "dir/sub/y"]);
+        assert!(metainfo.trackers().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            TorrentMetainfo::from_bytes(b"d4:infod4:name1:aee"),
+            Err(MetainfoError::MissingField("piece length"))
+        ));
+        assert!(matches!(
+            TorrentMetainfo::from_bytes(
+                b"d4:infod4:name2:..12:piece lengthi1e6:pieces0:6:lengthi1eee"
+            ),
+            Err(MetainfoError::InvalidField("path"))
+        ));
+    }
+}
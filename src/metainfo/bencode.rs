@@ -0,0 +1,235 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use super::MetainfoError;
+
+const MAX_DEPTH: usize = 64;
+
+/// Decoded bencode value, dictionary keys are kept as raw bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bencode {
+    Int(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Self>),
+    Dict(BTreeMap<Vec<u8>, Self>),
+}
+
+impl Bencode {
+    #[must_use]
+    pub const fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_bytes().and_then(|bytes| std::str::from_utf8(bytes).ok())
+    }
+
+    #[must_use]
+    pub fn as_list(&self) -> Option<&[Self]> {
+        match self {
+            Self::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_dict(&self) -> Option<&BTreeMap<Vec<u8>, Self>> {
+        match self {
+            Self::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// Value of `key` if `self` is a dictionary.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        self.as_dict()?.get(key.as_bytes())
+    }
+
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Int(int) => out.extend(format!("i{int}e").bytes()),
+            Self::Bytes(bytes) => encode_bytes(bytes, out),
+            Self::List(list) => {
+                out.push(b'l');
+                for value in list {
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+            Self::Dict(dict) => {
+                out.push(b'd');
+                for (key, value) in dict {
+                    encode_bytes(key, out);
+                    value.encode_into(out);
+                }
+                out.push(b'e');
+            }
+        }
+    }
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend(format!("{}:", bytes.len()).bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Decodes a whole bencode document.
+pub fn decode(data: &[u8]) -> Result<Bencode, MetainfoError> {
+    decode_with_info(data).map(|(value, _)| value)
+}
+
+/// Decodes a whole bencode document, also returning the byte range of the top level `info`
+/// value, which is what info hashes are computed over.
+pub(super) fn decode_with_info(
+    data: &[u8],
+) -> Result<(Bencode, Option<Range<usize>>), MetainfoError> {
+    let mut decoder = Decoder {
+        data,
+        pos: 0,
+        info: None,
+    };
+    let value = decoder.value(0)?;
+    if decoder.pos == data.len() {
+        Ok((value, decoder.info))
+    } else {
+        Err(MetainfoError::TrailingData(decoder.pos))
+    }
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    info: Option<Range<usize>>,
+}
+
+impl Decoder<'_> {
+    fn peek(&self) -> Result<u8, MetainfoError> {
+        self.data
+            .get(self.pos)
+            .copied()
+            .ok_or(MetainfoError::UnexpectedEof)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Bencode, MetainfoError> {
+        if depth > MAX_DEPTH {
+            return Err(MetainfoError::TooDeep);
+        }
+        match self.peek()? {
+            b'i' => {
+                self.pos += 1;
+                self.int(b'e').map(Bencode::Int)
+            }
+            b'0'..=b'9' => self.bytes().map(|bytes| Bencode::Bytes(bytes.to_vec())),
+            b'l' => {
+                self.pos += 1;
+                let mut list = Vec::new();
+                while self.peek()? != b'e' {
+                    list.push(self.value(depth + 1)?);
+                }
+                self.pos += 1;
+                Ok(Bencode::List(list))
+            }
+            b'd' => {
+                self.pos += 1;
+                let mut dict = BTreeMap::new();
+                while self.peek()? != b'e' {
+                    let key = self.bytes()?.to_vec();
+                    let start = self.pos;
+                    let value = self.value(depth + 1)?;
+                    if depth == 0 && key == b"info" {
+                        self.info = Some(start..self.pos);
+                    }
+                    dict.insert(key, value);
+                }
+                self.pos += 1;
+                Ok(Bencode::Dict(dict))
+            }
+            byte => Err(MetainfoError::UnexpectedByte(byte, self.pos)),
+        }
+    }
+
+    fn int(&mut self, end: u8) -> Result<i64, MetainfoError> {
+        let start = self.pos;
+        let len = self.data[start..]
+            .iter()
+            .position(|&byte| byte == end)
+            .ok_or(MetainfoError::UnexpectedEof)?;
+        self.pos += len + 1;
+        let digits = &self.data[start..start + len];
+        let valid = match digits {
+            [b'-', b'0', ..] | [b'0', _, ..] | [] | [b'-'] => false,
+            [b'-', rest @ ..] | rest => rest.iter().all(u8::is_ascii_digit),
+        };
+        std::str::from_utf8(digits)
+            .ok()
+            .filter(|_| valid)
+            .and_then(|digits| digits.parse().ok())
+            .ok_or(MetainfoError::InvalidInteger(start))
+    }
+
+    fn bytes(&mut self) -> Result<&[u8], MetainfoError> {
+        let start = self.pos;
+        if !self.peek()?.is_ascii_digit() {
+            return Err(MetainfoError::UnexpectedByte(self.peek()?, start));
+        }
+        let len = usize::try_from(self.int(b':')?)
+            .map_err(|_| MetainfoError::InvalidInteger(start))?;
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or(MetainfoError::UnexpectedEof)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, decode_with_info, Bencode};
+    use crate::metainfo::MetainfoError;
+
+    #[test]
+    fn round_trip() -> Result<(), MetainfoError> {
+        let data = b"d3:bari-12e3:fool4:spami0eee";
+        let value = decode(data)?;
+        assert_eq!(value.get("bar").and_then(Bencode::as_int), Some(-12));
+        assert_eq!(value.encode(), data);
+        Ok(())
+    }
+
+    #[test]
+    fn info_range() -> Result<(), MetainfoError> {
+        let data = b"d4:infod1:ai1eee";
+        let (_, info) = decode_with_info(data)?;
+        assert_eq!(info.map(|range| &data[range]), Some(&b"d1:ai1ee"[..]));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(decode(b"i-0e"), Err(MetainfoError::InvalidInteger(1))));
+        assert!(matches!(decode(b"i03e"), Err(MetainfoError::InvalidInteger(1))));
+        assert!(matches!(decode(b"5:ab"), Err(MetainfoError::UnexpectedEof)));
+        assert!(matches!(decode(b"i1ei2e"), Err(MetainfoError::TrailingData(3))));
+        assert!(matches!(decode(b"x"), Err(MetainfoError::UnexpectedByte(b'x', 0))));
+    }
+}
@@ -0,0 +1,501 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+
+use super::{Bencode, MetainfoError, TorrentMetainfo};
+use crate::{torrent_stuff::Torrent, DelugeApiError};
+
+/// Size of the leaves of v2 merkle trees.
+const BLOCK_SIZE: usize = 16 * 1024;
+
+/// Format of a created torrent, serialized as Deluge's `TorrentFormat`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MetaVersion {
+    #[default]
+    V1,
+    V2,
+    /// Both v1 and v2 metadata, files are padded to piece boundaries in the v1 part.
+    Hybrid,
+}
+
+impl MetaVersion {
+    const fn has_v1(self) -> bool {
+        matches!(self, Self::V1 | Self::Hybrid)
+    }
+
+    const fn has_v2(self) -> bool {
+        matches!(self, Self::V2 | Self::Hybrid)
+    }
+}
+
+struct SourceFile {
+    /// Path relative to the torrent's root, empty for single file torrents.
+    components: Vec<String>,
+    path: Utf8PathBuf,
+    length: u64,
+}
+
+/// Part of the v1 byte stream, `None` for padding.
+struct Span<'a> {
+    start: u64,
+    length: u64,
+    path: Option<&'a Utf8Path>,
+}
+
+impl TorrentMetainfo {
+    /// Hashes the content at `path` locally in the torrent's format (v1 by default), using
+    /// every available core. The torrent's own path and target are the daemon's, so `path` is
+    /// usually [`crate::torrent_stuff::PathMappings::to_local`] of the former and the
+    /// target isn't written, save [`Self::as_bytes`] for that.
+    ///
+    /// The result can be added with [`crate::deluge_interface::CoreClient::add_torrent_metainfo`]
+    /// and [`crate::torrent_stuff::TorrentOption::SeedMode`] to skip rechecking.
+    pub fn create(torrent: &Torrent, path: &Utf8Path) -> Result<Self, DelugeApiError> {
+        let version = torrent.format.unwrap_or_default();
+        let piece_length = u64::try_from(torrent.piece_length)?;
+        let valid_piece_length = if version.has_v2() {
+            piece_length.is_power_of_two() && piece_length >= BLOCK_SIZE as u64
+        } else {
+            piece_length > 0
+        };
+        if !valid_piece_length {
+            return Err(MetainfoError::InvalidField("piece length").into());
+        }
+        let name = path
+            .file_name()
+            .ok_or(MetainfoError::InvalidField("name"))?;
+        let files = collect_files(path)?;
+
+        let mut info = BTreeMap::new();
+        info.insert(key("name"), string(name));
+        info.insert(
+            key("piece length"),
+            Bencode::Int(torrent.piece_length.try_into()?),
+        );
+        if torrent.private == Some(true) {
+            info.insert(key("private"), Bencode::Int(1));
+        }
+        if version.has_v1() {
+            v1_info(
+                &files,
+                piece_length,
+                version == MetaVersion::Hybrid,
+                &mut info,
+            )?;
+        }
+        let mut piece_layers = BTreeMap::new();
+        if version.has_v2() {
+            let tree = v2_file_tree(&files, piece_length, &mut piece_layers)?;
+            let tree = if files[0].components.is_empty() {
+                Bencode::Dict(BTreeMap::from([(key(name), tree)]))
+            } else {
+                tree
+            };
+            info.insert(key("file tree"), tree);
+            info.insert(key("meta version"), Bencode::Int(2));
+        }
+
+        let mut root = BTreeMap::new();
+        // Same as Deluge, the announce list replaces the main tracker when there's one
+        let tiers = torrent
+            .trackers
+            .clone()
+            .unwrap_or_else(|| vec![vec![torrent.tracker.clone()]]);
+        root.insert(key("announce"), string(torrent.tracker.as_str()));
+        root.insert(
+            key("announce-list"),
+            Bencode::List(
+                tiers
+                    .iter()
+                    .map(|tier| {
+                        Bencode::List(tier.iter().map(|url| string(url.as_str())).collect())
+                    })
+                    .collect(),
+            ),
+        );
+        if let Some(comment) = &torrent.comment {
+            root.insert(key("comment"), string(comment));
+        }
+        if let Some(created_by) = &torrent.created_by {
+            root.insert(key("created by"), string(created_by));
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_secs());
+        root.insert(key("creation date"), Bencode::Int(now.try_into()?));
+        if let Some(webseeds) = &torrent.webseeds {
+            root.insert(
+                key("url-list"),
+                Bencode::List(webseeds.iter().map(|url| string(url)).collect()),
+            );
+        }
+        root.insert(key("info"), Bencode::Dict(info));
+        if version.has_v2() {
+            root.insert(key("piece layers"), Bencode::Dict(piece_layers));
+        }
+
+        Ok(Self::from_bytes(&Bencode::Dict(root).encode())?)
+    }
+}
+
+fn key(key: &str) -> Vec<u8> {
+    key.as_bytes().to_vec()
+}
+
+fn string(value: &str) -> Bencode {
+    Bencode::Bytes(key(value))
+}
+
+fn collect_files(root: &Utf8Path) -> Result<Vec<SourceFile>, DelugeApiError> {
+    let metadata = fs::metadata(root)?;
+    if metadata.is_file() {
+        return Ok(vec![SourceFile {
+            components: Vec::new(),
+            path: root.to_owned(),
+            length: metadata.len(),
+        }]);
+    }
+    let mut files = Vec::new();
+    walk(root, &mut Vec::new(), &mut files)?;
+    if files.is_empty() {
+        return Err(MetainfoError::MissingField("files").into());
+    }
+    // Same order as the v2 file tree's keys
+    files.sort_by(|a, b| a.components.cmp(&b.components));
+    Ok(files)
+}
+
+fn walk(
+    dir: &Utf8Path,
+    components: &mut Vec<String>,
+    files: &mut Vec<SourceFile>,
+) -> Result<(), DelugeApiError> {
+    for entry in dir.read_dir_utf8()? {
+        let entry = entry?;
+        let mut metadata = fs::symlink_metadata(entry.path())?;
+        if metadata.is_symlink() {
+            // Linked files are followed, linked folders are skipped as they could loop back
+            match fs::metadata(entry.path()) {
+                Ok(target) if target.is_file() => metadata = target,
+                _ => continue,
+            }
+        }
+        components.push(entry.file_name().to_owned());
+        if metadata.is_dir() {
+            walk(entry.path(), components, files)?;
+        } else {
+            files.push(SourceFile {
+                components: components.clone(),
+                path: entry.path().to_owned(),
+                length: metadata.len(),
+            });
+        }
+        components.pop();
+    }
+    Ok(())
+}
+
+/// Runs `job` for `0..count` on every available core, keeping the results in order.
+fn run_parallel<T, F>(count: usize, job: F) -> io::Result<Vec<T>>
+where
+    T: Send,
+    F: Fn(usize) -> io::Result<T> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(count.max(1));
+    let mut results: Vec<Option<T>> = (0..count).map(|_| None).collect();
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut out = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= count {
+                            return Ok(out);
+                        }
+                        out.push((index, job(index)?));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            let out: io::Result<Vec<_>> = handle.join().expect("hashing thread panicked");
+            for (index, result) in out? {
+                results[index] = Some(result);
+            }
+        }
+        Ok::<_, io::Error>(())
+    })?;
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Reads `buf.len()` bytes of the spans starting at `start`, padding reads as zeros.
+fn read_spans(spans: &[Span<'_>], start: u64, buf: &mut [u8]) -> io::Result<()> {
+    let end = start + buf.len() as u64;
+    let first = spans.partition_point(|span| span.start + span.length <= start);
+    for span in spans[first..].iter().take_while(|span| span.start < end) {
+        let from = start.max(span.start);
+        let to = end.min(span.start + span.length);
+        let out = &mut buf[usize::try_from(from - start).map_err(io::Error::other)?
+            ..usize::try_from(to - start).map_err(io::Error::other)?];
+        match span.path {
+            Some(path) => read_at(path, from - span.start, out)?,
+            None => out.fill(0),
+        }
+    }
+    Ok(())
+}
+
+fn read_at(path: &Utf8Path, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buf)
+}
+
+fn v1_info(
+    files: &[SourceFile],
+    piece_length: u64,
+    pad: bool,
+    info: &mut BTreeMap<Vec<u8>, Bencode>,
+) -> Result<(), DelugeApiError> {
+    let mut spans = Vec::new();
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    for (i, file) in files.iter().enumerate() {
+        spans.push(Span {
+            start: offset,
+            length: file.length,
+            path: Some(&file.path),
+        });
+        offset += file.length;
+        entries.push(Bencode::Dict(BTreeMap::from([
+            (key("length"), Bencode::Int(file.length.try_into()?)),
+            (
+                key("path"),
+                Bencode::List(file.components.iter().map(|c| string(c)).collect()),
+            ),
+        ])));
+        let remainder = file.length % piece_length;
+        if pad && i + 1 < files.len() && remainder != 0 {
+            let length = piece_length - remainder;
+            spans.push(Span {
+                start: offset,
+                length,
+                path: None,
+            });
+            offset += length;
+            entries.push(Bencode::Dict(BTreeMap::from([
+                (key("attr"), string("p")),
+                (key("length"), Bencode::Int(length.try_into()?)),
+                (
+                    key("path"),
+                    Bencode::List(vec![string(".pad"), string(&length.to_string())]),
+                ),
+            ])));
+        }
+    }
+
+    let count = usize::try_from(offset.div_ceil(piece_length))?;
+    let pieces = run_parallel(count, |piece| {
+        let start = piece as u64 * piece_length;
+        let length = piece_length.min(offset - start);
+        let mut buf = vec![0; usize::try_from(length).map_err(io::Error::other)?];
+        read_spans(&spans, start, &mut buf)?;
+        Ok(Sha1::digest(&buf))
+    })?;
+    info.insert(key("pieces"), Bencode::Bytes(pieces.concat()));
+    if files[0].components.is_empty() {
+        info.insert(key("length"), Bencode::Int(files[0].length.try_into()?));
+    } else {
+        info.insert(key("files"), Bencode::List(entries));
+    }
+    Ok(())
+}
+
+/// Builds the v2 file tree, adding the piece layers of files larger than a piece.
+fn v2_file_tree(
+    files: &[SourceFile],
+    piece_length: u64,
+    piece_layers: &mut BTreeMap<Vec<u8>, Bencode>,
+) -> Result<Bencode, DelugeApiError> {
+    let jobs: Vec<(&SourceFile, u64)> = files
+        .iter()
+        .flat_map(|file| (0..file.length.div_ceil(piece_length)).map(move |piece| (file, piece)))
+        .collect();
+    let mut leaves = run_parallel(jobs.len(), |job| {
+        let (file, piece) = jobs[job];
+        let start = piece * piece_length;
+        let mut buf = vec![
+            0;
+            usize::try_from(piece_length.min(file.length - start))
+                .map_err(io::Error::other)?
+        ];
+        read_at(&file.path, start, &mut buf)?;
+        Ok(buf
+            .chunks(BLOCK_SIZE)
+            .map(|block| <[u8; 32]>::from(Sha256::digest(block)))
+            .collect::<Vec<_>>())
+    })?
+    .into_iter();
+
+    let blocks_per_piece = usize::try_from(piece_length)? / BLOCK_SIZE;
+    let mut tree = BTreeMap::new();
+    for file in files {
+        let pieces = usize::try_from(file.length.div_ceil(piece_length))?;
+        let mut node = BTreeMap::from([(key("length"), Bencode::Int(file.length.try_into()?))]);
+        if pieces > 0 {
+            let file_leaves = leaves.by_ref().take(pieces).flatten().collect();
+            let (root, layer) = merkle_root(file_leaves, blocks_per_piece);
+            if file.length > piece_length {
+                piece_layers.insert(root.to_vec(), Bencode::Bytes(layer[..pieces].concat()));
+            }
+            node.insert(key("pieces root"), Bencode::Bytes(root.to_vec()));
+        }
+        insert_node(&mut tree, &file.components, node);
+    }
+    Ok(Bencode::Dict(tree))
+}
+
+/// Root of the merkle tree over `leaves` padded with zero hashes, and the layer whose nodes
+/// cover `blocks_per_piece` leaves (empty if the tree is smaller than a piece).
+fn merkle_root(mut leaves: Vec<[u8; 32]>, blocks_per_piece: usize) -> ([u8; 32], Vec<[u8; 32]>) {
+    leaves.resize(leaves.len().next_power_of_two(), [0; 32]);
+    let mut layer = leaves;
+    let mut piece_layer = Vec::new();
+    let mut blocks_per_node = 1;
+    loop {
+        if blocks_per_node == blocks_per_piece {
+            piece_layer.clone_from(&layer);
+        }
+        if layer.len() == 1 {
+            return (layer[0], piece_layer);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| {
+                Sha256::new()
+                    .chain_update(pair[0])
+                    .chain_update(pair[1])
+                    .finalize()
+                    .into()
+            })
+            .collect();
+        blocks_per_node *= 2;
+    }
+}
+
+fn insert_node(
+    tree: &mut BTreeMap<Vec<u8>, Bencode>,
+    components: &[String],
+    file: BTreeMap<Vec<u8>, Bencode>,
+) {
+    match components {
+        [] => {
+            tree.insert(Vec::new(), Bencode::Dict(file));
+        }
+        [first, rest @ ..] => {
+            let child = tree
+                .entry(key(first))
+                .or_insert_with(|| Bencode::Dict(BTreeMap::new()));
+            if let Bencode::Dict(child) = child {
+                insert_node(child, rest, file);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{error::Error, fs};
+
+    use camino::Utf8PathBuf;
+    use reqwest::Url;
+    use serde_json::json;
+
+    use super::MetaVersion;
+    use crate::{metainfo::TorrentMetainfo, torrent_stuff::TorrentBuilder};
+
+    #[test]
+    fn create() -> Result<(), Box<dyn Error>> {
+        let dir = Utf8PathBuf::try_from(std::env::temp_dir())?
+            .join(format!("deluge-rpc-api-create-{}", std::process::id()));
+        let payload = dir.join("payload");
+        fs::create_dir_all(payload.join("sub"))?;
+        fs::write(
+            payload.join("a"),
+            (0..40000_u32)
+                .map(|i| u8::try_from(i % 251).unwrap_or_default())
+                .collect::<Vec<_>>(),
+        )?;
+        fs::write(payload.join("sub/b"), "hello")?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("..", payload.join("sub/loop"))?;
+        let tracker = Url::parse("http://tracker.example/announce")?;
+        let torrent = |format| {
+            let mut builder =
+                TorrentBuilder::new("/downloads/payload".into(), tracker.clone(), 16384);
+            builder.with_format(format);
+            builder.build()
+        };
+
+        let v1 = TorrentMetainfo::create(&torrent(MetaVersion::V1), &payload)?;
+        assert_eq!(
+            v1.id().to_string(),
+            "25b683ee5b9287506db8fc0df362dbf99fdfbd0c"
+        );
+        let paths: Vec<_> = v1.files().iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["payload/a", "payload/sub/b"]);
+        assert_eq!(
+            v1.trackers()[0][0].as_str(),
+            "http://tracker.example/announce"
+        );
+
+        let v2 = TorrentMetainfo::create(&torrent(MetaVersion::V2), &payload)?;
+        assert_eq!(
+            v2.info_hash_v2().map(|hash| hash.to_string()).as_deref(),
+            Some("5498b97d59ab2b516f0e14de7c7f331c6a998fc4d394f9d4ea99cc3013d41aee")
+        );
+        assert_eq!(v2.files(), v1.files());
+        assert_eq!(
+            json!(v2.id()),
+            json!("5498b97d59ab2b516f0e14de7c7f331c6a998fc4")
+        );
+
+        let hybrid = TorrentMetainfo::create(&torrent(MetaVersion::Hybrid), &payload)?;
+        assert_eq!(
+            hybrid.id().to_string(),
+            "bf0030a63f339f3cde5e9d340816518e59ffa1aa"
+        );
+        assert_eq!(
+            hybrid
+                .info_hash_v2()
+                .map(|hash| hash.to_string())
+                .as_deref(),
+            Some("789046218c225f3b6cd24c07a81f681c801fe8b61ca351e02d5ab75e544df8be")
+        );
+        assert_eq!(hybrid.files(), v1.files());
+
+        let single =
+            TorrentMetainfo::create(&torrent(MetaVersion::Hybrid), &payload.join("sub/b"))?;
+        assert_eq!(single.name(), "b");
+        assert_eq!(single.total_size(), 5);
+
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
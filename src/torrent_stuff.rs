@@ -1,14 +1,47 @@
 use std::fmt;
 
-use camino::Utf8PathBuf;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use reqwest::Url;
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
+mod blocklist;
+mod core_config;
+mod execute_command;
+mod info_hash;
+mod label_options;
+mod magnet_uri;
+mod plugin_config;
+mod proxy_config;
+mod remote_path;
+mod schedule;
+mod session_status;
+mod stats;
+mod torrent_info;
 mod torrent_options;
+mod watch_dir;
+mod web_config;
+pub use blocklist::*;
+pub use core_config::*;
+pub use execute_command::*;
+pub use info_hash::*;
+pub use label_options::*;
+pub use magnet_uri::*;
+pub use plugin_config::*;
+pub use proxy_config::*;
+pub use remote_path::*;
+pub use schedule::*;
 use serde_json::{json, Value};
+pub use session_status::*;
+pub use stats::*;
+pub use torrent_info::*;
 pub use torrent_options::*;
+pub use watch_dir::*;
+pub use web_config::*;
 
-use crate::DelugeApiError;
+use crate::{
+    metainfo::{MetaVersion, TorrentMetainfo},
+    DelugeApiError,
+};
 
 #[derive(Debug)]
 pub struct TorrentTracker {
@@ -54,7 +87,7 @@ impl TorrentResponse<()> {
     pub(crate) fn into_empty_result(self) -> Result<(), DelugeApiError> {
         let out = self.into_result();
         match out {
-            Ok(_) => Ok(()),
+            Ok(()) => Ok(()),
             Err(err) => match err {
                 DelugeApiError::EmptyResult => Ok(()),
                 _ => Err(err),
@@ -82,24 +115,44 @@ pub(crate) struct ErrorValue {
     pub message: String,
 }
 
+/// Options read from a torrent's status, see [`TorrentOption::STATUS_KEYS`].
+pub(crate) struct StatusOptions(pub TorrentOptions);
+
+impl<'de> Deserialize<'de> for StatusOptions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut status = serde_json::Map::deserialize(deserializer)?;
+        if let Some(value) = status.remove("prioritize_first_last") {
+            status.insert("prioritize_first_last_pieces".to_owned(), value);
+        }
+        TorrentOptions::deserialize(Value::Object(status))
+            .map(Self)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Debug)]
 pub struct TorrentBuilder {
-    path: Utf8PathBuf,
-    tracker: TorrentTracker,
+    path: RemotePath,
+    tracker: Url,
     piece_length: usize,
     comment: Option<String>,
-    target: Option<Utf8PathBuf>,
+    target: Option<RemotePath>,
     webseeds: Option<Vec<String>>,
     private: Option<bool>,
     created_by: Option<String>,
-    trackers: Option<Vec<String>>,
+    trackers: Option<Vec<Vec<Url>>>,
+    format: Option<MetaVersion>,
 }
 
 impl TorrentBuilder {
+    /// `path` is the content's path on the daemon, see [`PathMappings::to_remote`].
     // //! Make const when const_precise_live_drops reaches stable
     #[allow(clippy::missing_const_for_fn)]
     #[must_use]
-    pub const fn new(path: Utf8PathBuf, tracker: TorrentTracker, piece_length: usize) -> Self {
+    pub const fn new(path: RemotePath, tracker: Url, piece_length: usize) -> Self {
         Self {
             path,
             tracker,
@@ -110,6 +163,7 @@ impl TorrentBuilder {
             private: None,
             created_by: None,
             trackers: None,
+            format: None,
         }
     }
 
@@ -117,7 +171,8 @@ impl TorrentBuilder {
         self.comment = comment.into();
         self
     }
-    pub fn with_target(&mut self, target: Utf8PathBuf) -> &mut Self {
+    /// Path on the daemon where it writes the `.torrent` file.
+    pub fn with_target(&mut self, target: RemotePath) -> &mut Self {
         self.target = target.into();
         self
     }
@@ -129,10 +184,17 @@ impl TorrentBuilder {
         self.created_by = author.into();
         self
     }
-    pub fn with_trackers(&mut self, trackers: Vec<String>) -> &mut Self {
+    /// Tiers of the announce list, they should include the main tracker.
+    pub fn with_trackers(&mut self, trackers: Vec<Vec<Url>>) -> &mut Self {
         self.trackers = trackers.into();
         self
     }
+    /// Also used by [`TorrentMetainfo::create`]. Only supported since Deluge 2.1, older
+    /// daemons always create v1 torrents.
+    pub fn with_format(&mut self, format: MetaVersion) -> &mut Self {
+        self.format = format.into();
+        self
+    }
 
     pub fn private(&mut self, enable: bool) -> &mut Self {
         self.private = enable.into();
@@ -153,39 +215,93 @@ impl TorrentBuilder {
             private: self.private,
             created_by: self.created_by,
             trackers: self.trackers,
+            format: self.format,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct Torrent {
-    path: Utf8PathBuf,
-    tracker: TorrentTracker,
-    piece_length: usize,
-    comment: Option<String>,
-    target: Option<Utf8PathBuf>,
-    webseeds: Option<Vec<String>>,
-    private: Option<bool>,
-    created_by: Option<String>,
-    trackers: Option<Vec<String>>,
+    pub(crate) path: RemotePath,
+    pub(crate) tracker: Url,
+    pub(crate) piece_length: usize,
+    pub(crate) comment: Option<String>,
+    pub(crate) target: Option<RemotePath>,
+    pub(crate) webseeds: Option<Vec<String>>,
+    pub(crate) private: Option<bool>,
+    pub(crate) created_by: Option<String>,
+    pub(crate) trackers: Option<Vec<Vec<Url>>>,
+    pub(crate) format: Option<MetaVersion>,
 }
 
 impl Torrent {
-    pub(crate) fn into_list(self) -> Vec<Value> {
-        vec![
+    /// Parameters of `core.create_torrent`.
+    pub(crate) fn into_list(self, add_to_session: bool) -> Vec<Value> {
+        let trackers = self.trackers.as_ref().map(|tiers| {
+            tiers
+                .iter()
+                .map(|tier| tier.iter().map(Url::as_str).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        });
+        let mut list = vec![
             json!(self.path),
-            json!(self.tracker),
+            json!(self.tracker.as_str()),
             json!(self.piece_length),
             json!(self.comment),
             json!(self.target),
             json!(self.webseeds),
-            json!(self.private),
+            json!(self.private.unwrap_or_default()),
             json!(self.created_by),
-            json!(self.trackers),
-        ]
+            json!(trackers),
+            json!(add_to_session),
+        ];
+        if let Some(format) = self.format {
+            list.push(json!(format));
+        }
+        list
+    }
+}
+
+/// Result of `core.create_torrent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedTorrent {
+    /// Name of the `.torrent` file written by the daemon.
+    pub filename: String,
+    /// Base64 encoded `.torrent` file.
+    pub data: String,
+    pub torrent_id: TorrentId,
+}
+
+impl CreatedTorrent {
+    pub(crate) fn new(filename: String, data: String) -> Result<Self, DelugeApiError> {
+        let torrent_id = TorrentMetainfo::from_bytes(&STANDARD.decode(&data)?)?.id();
+        Ok(Self {
+            filename,
+            data,
+            torrent_id,
+        })
+    }
+
+    pub fn metainfo(&self) -> Result<TorrentMetainfo, DelugeApiError> {
+        Ok(TorrentMetainfo::from_bytes(&STANDARD.decode(&self.data)?)?)
     }
 }
 
+/// Response of deluge-web's `/upload` endpoint, `files` are temporary paths on the web server.
+#[derive(Deserialize, Debug)]
+pub(crate) struct UploadResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub files: Vec<RemotePath>,
+}
+
+/// Entry of the list taken by `web.add_torrents`.
+#[derive(Serialize, Debug)]
+pub(crate) struct WebTorrent<'a> {
+    pub path: &'a RemotePath,
+    pub options: &'a TorrentOptions,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Account {
     pub username: String,
@@ -202,11 +318,46 @@ pub struct Host {
     pub username: String,
 }
 
+/// Result of `web.get_plugins`.
+#[derive(Deserialize, Debug)]
+pub struct Plugins {
+    pub enabled_plugins: Vec<String>,
+    pub available_plugins: Vec<String>,
+}
+
+/// Metadata of a plugin, missing fields are `None`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    #[serde(rename = "Version")]
+    pub version: Option<String>,
+    #[serde(rename = "Author")]
+    pub author: Option<String>,
+    #[serde(rename = "Author-email")]
+    pub author_email: Option<String>,
+    #[serde(rename = "Description")]
+    pub description: Option<String>,
+    #[serde(rename = "Home-page")]
+    pub home_page: Option<String>,
+    #[serde(rename = "License")]
+    pub license: Option<String>,
+}
+
 #[cfg(test)]
 mod test {
     use std::error::Error;
 
-    use crate::torrent_stuff::Host;
+    use reqwest::Url;
+    use serde_json::json;
+
+    use crate::{
+        metainfo::MetaVersion,
+        torrent_stuff::{
+            Host, PluginInfo, TorrentBuilder, TorrentOption, TorrentOptions, UploadResponse,
+            WebTorrent,
+        },
+    };
 
     #[test]
     fn test1() -> Result<(), Box<dyn Error>> {
@@ -220,4 +371,68 @@ mod test {
         assert_eq!(json.username, "4");
         Ok(())
     }
+
+    #[test]
+    fn create_torrent_params() -> Result<(), Box<dyn Error>> {
+        let tracker = Url::parse("http://a.example/announce")?;
+        let mut builder = TorrentBuilder::new("/data/dir".into(), tracker.clone(), 262_144);
+        builder
+            .with_trackers(vec![vec![tracker], vec![Url::parse("udp://b.example:80")?]])
+            .with_format(MetaVersion::Hybrid);
+        assert_eq!(
+            json!(builder.build().into_list(false)),
+            json!([
+                "/data/dir",
+                "http://a.example/announce",
+                262_144,
+                null,
+                null,
+                null,
+                false,
+                null,
+                [["http://a.example/announce"], ["udp://b.example:80"]],
+                false,
+                "hybrid"
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn upload_response() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"success": true, "files": ["/tmp/delugeweb-x1/a.torrent"]}"#;
+        let response: UploadResponse = serde_json::from_str(json)?;
+        assert!(response.success);
+        assert_eq!(response.files, ["/tmp/delugeweb-x1/a.torrent"]);
+        let response: UploadResponse = serde_json::from_str(r#"{"success": false}"#)?;
+        assert!(!response.success);
+        assert!(response.files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn web_torrent() {
+        let mut options = TorrentOptions::new();
+        options.insert(TorrentOption::AddPaused(true));
+        let torrent = WebTorrent {
+            path: &"/tmp/delugeweb-x1/a.torrent".into(),
+            options: &options,
+        };
+        assert_eq!(
+            r#"{"options":{"add_paused":true},"path":"/tmp/delugeweb-x1/a.torrent"}"#,
+            format!("{}", json!(torrent))
+        );
+    }
+
+    #[test]
+    fn plugin_info() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"Name": "Label", "Version": "0.3", "Author": "Martijn Voncken",
+            "Author-email": "mvoncken@gmail.com", "Description": "Allows labels to be assigned",
+            "Home-page": "http://deluge-torrent.org", "License": "GPLv3", "Summary": null}"#;
+        let info: PluginInfo = serde_json::from_str(json)?;
+        assert_eq!(info.name.as_deref(), Some("Label"));
+        assert_eq!(info.version.as_deref(), Some("0.3"));
+        assert_eq!(info.author_email.as_deref(), Some("mvoncken@gmail.com"));
+        Ok(())
+    }
 }
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration of the Blocklist plugin.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlocklistConfig {
+    pub url: String,
+    pub load_on_start: bool,
+    pub check_after_days: u32,
+    /// Detected by the plugin, e.g. `GZip`.
+    pub list_compression: String,
+    /// Detected by the plugin, e.g. `PeerGuardian`.
+    pub list_type: String,
+    /// Unix timestamp of the last import.
+    pub last_update: f64,
+    /// In bytes.
+    pub list_size: u64,
+    /// Download timeout in seconds.
+    pub timeout: u64,
+    pub try_times: u32,
+    /// IP ranges which are never blocked.
+    #[serde(rename = "whitelisted")]
+    pub whitelist: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocklistState {
+    Downloading,
+    Importing,
+    Idle,
+}
+
+/// Result of `blocklist.get_status`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct BlocklistStatus {
+    pub state: BlocklistState,
+    pub up_to_date: bool,
+    pub num_whited: u64,
+    pub num_blocked: u64,
+    /// Between 0 and 1.
+    pub file_progress: f64,
+    pub file_url: String,
+    pub file_size: u64,
+    pub file_date: f64,
+    pub file_type: String,
+    #[serde(rename = "whitelisted")]
+    pub whitelist: Vec<String>,
+}
+
+impl BlocklistStatus {
+    /// The blocklist is imported and blocks at least one range.
+    #[must_use]
+    pub fn is_loaded(&self) -> bool {
+        self.state == BlocklistState::Idle && self.num_blocked > 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::{BlocklistState, BlocklistStatus};
+
+    #[test]
+    fn blocklist_status() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"state": "Idle", "up_to_date": true, "num_whited": 0,
+            "num_blocked": 241532, "file_progress": 1.0, "file_url": "http://example.org/list.gz",
+            "file_size": 1024, "file_date": 1650000000.0, "file_type": "PeerGuardian (GZip)",
+            "whitelisted": ["10.0.0.0/8"]}"#;
+        let status: BlocklistStatus = serde_json::from_str(json)?;
+        assert_eq!(status.state, BlocklistState::Idle);
+        assert_eq!(status.whitelist, ["10.0.0.0/8"]);
+        assert!(status.is_loaded());
+        Ok(())
+    }
+}
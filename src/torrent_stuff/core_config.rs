@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use serde::{de, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::enum_map::{serializable_enum, EnumMap};
+
+use super::{ProxyConfig, RemotePath};
+
+/// Encryption policy for incoming (`enc_in_policy`) and outgoing (`enc_out_policy`)
+/// connections.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EncryptionPolicy {
+    Forced = 0,
+    #[default]
+    Enabled = 1,
+    Disabled = 2,
+}
+
+/// Encryption level (`enc_level`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum EncryptionLevel {
+    Plaintext = 0,
+    Rc4 = 1,
+    #[default]
+    Both = 2,
+}
+
+macro_rules! int_enum_serde {
+    ($name:ident { $($value:literal => $variant:ident),* $(,)? }) => {
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_u8(*self as u8)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                match u8::deserialize(deserializer)? {
+                    $($value => Ok(Self::$variant),)*
+                    other => Err(de::Error::custom(format!(
+                        concat!("invalid ", stringify!($name), " {}"),
+                        other
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+int_enum_serde!(EncryptionPolicy { 0 => Forced, 1 => Enabled, 2 => Disabled });
+int_enum_serde!(EncryptionLevel { 0 => Plaintext, 1 => Rc4, 2 => Both });
+
+/// Generates [`CoreConfig`] and [`ConfigOption`] (and through it [`ConfigKey`]) from a single
+/// list of `Variant(type) => key` so they can't get out of sync.
+macro_rules! core_config {
+    ($($(#[doc = $doc:literal])* $variant:ident($ty:ty) => $key:ident,)*) => {
+        /// Deluge's `core.conf`, as returned by `core.get_config`.
+        #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+        #[serde(default)]
+        pub struct CoreConfig {
+            $($(#[doc = $doc])* pub $key: $ty,)*
+            /// Keys not modeled above.
+            #[serde(flatten)]
+            pub other: HashMap<String, Value>,
+        }
+
+        impl CoreConfig {
+            #[must_use]
+            pub fn get(&self, key: ConfigKey) -> ConfigOption {
+                match key {
+                    $(ConfigKey::$variant => ConfigOption::$variant(self.$key.clone()),)*
+                }
+            }
+        }
+
+        serializable_enum! {
+            /// Value of a single key of [`CoreConfig`], used for partial updates through
+            /// `core.set_config`.
+            #[derive(Debug, Clone, PartialEq)]
+            pub enum ConfigOption: ConfigKey {
+                $($(#[doc = $doc])* $variant($ty) => $key,)*
+            }
+        }
+    };
+}
+
+core_config! {
+    AddPaused(bool) => add_paused,
+    AllowRemote(bool) => allow_remote,
+    AutoManagePreferSeeds(bool) => auto_manage_prefer_seeds,
+    AutoManaged(bool) => auto_managed,
+    /// In seconds.
+    CacheExpiry(i64) => cache_expiry,
+    /// In 16 KiB blocks.
+    CacheSize(i64) => cache_size,
+    CopyTorrentFile(bool) => copy_torrent_file,
+    DaemonPort(u16) => daemon_port,
+    DelCopyTorrentFile(bool) => del_copy_torrent_file,
+    Dht(bool) => dht,
+    DontCountSlowTorrents(bool) => dont_count_slow_torrents,
+    DownloadLocation(RemotePath) => download_location,
+    EnabledPlugins(Vec<String>) => enabled_plugins,
+    EncInPolicy(EncryptionPolicy) => enc_in_policy,
+    EncLevel(EncryptionLevel) => enc_level,
+    EncOutPolicy(EncryptionPolicy) => enc_out_policy,
+    GeoipDbLocation(RemotePath) => geoip_db_location,
+    IgnoreLimitsOnLocalNetwork(bool) => ignore_limits_on_local_network,
+    ListenInterface(String) => listen_interface,
+    /// Range of ports to listen on, used when `random_port` is `false`.
+    ListenPorts((u16, u16)) => listen_ports,
+    ListenRandomPort(Option<u16>) => listen_random_port,
+    ListenReusePort(bool) => listen_reuse_port,
+    ListenUseSysPort(bool) => listen_use_sys_port,
+    Lsd(bool) => lsd,
+    MaxActiveDownloading(i64) => max_active_downloading,
+    MaxActiveLimit(i64) => max_active_limit,
+    MaxActiveSeeding(i64) => max_active_seeding,
+    MaxConnectionsGlobal(i64) => max_connections_global,
+    MaxConnectionsPerSecond(i64) => max_connections_per_second,
+    MaxConnectionsPerTorrent(i64) => max_connections_per_torrent,
+    /// In KiB/s, `-1` means unlimited.
+    MaxDownloadSpeed(f64) => max_download_speed,
+    /// In KiB/s, `-1` means unlimited.
+    MaxDownloadSpeedPerTorrent(f64) => max_download_speed_per_torrent,
+    MaxHalfOpenConnections(i64) => max_half_open_connections,
+    MaxUploadSlotsGlobal(i64) => max_upload_slots_global,
+    MaxUploadSlotsPerTorrent(i64) => max_upload_slots_per_torrent,
+    /// In KiB/s, `-1` means unlimited.
+    MaxUploadSpeed(f64) => max_upload_speed,
+    /// In KiB/s, `-1` means unlimited.
+    MaxUploadSpeedPerTorrent(f64) => max_upload_speed_per_torrent,
+    MoveCompleted(bool) => move_completed,
+    MoveCompletedPath(RemotePath) => move_completed_path,
+    Natpmp(bool) => natpmp,
+    NewReleaseCheck(bool) => new_release_check,
+    OutgoingInterface(String) => outgoing_interface,
+    OutgoingPorts((u16, u16)) => outgoing_ports,
+    PeerTos(String) => peer_tos,
+    PluginsLocation(RemotePath) => plugins_location,
+    PreAllocateStorage(bool) => pre_allocate_storage,
+    PrioritizeFirstLastPieces(bool) => prioritize_first_last_pieces,
+    Proxy(ProxyConfig) => proxy,
+    QueueNewToTop(bool) => queue_new_to_top,
+    RandomOutgoingPorts(bool) => random_outgoing_ports,
+    RandomPort(bool) => random_port,
+    RateLimitIpOverhead(bool) => rate_limit_ip_overhead,
+    RemoveSeedAtRatio(bool) => remove_seed_at_ratio,
+    SendInfo(bool) => send_info,
+    SequentialDownload(bool) => sequential_download,
+    /// In minutes.
+    SeedTimeLimit(i64) => seed_time_limit,
+    SeedTimeRatioLimit(f64) => seed_time_ratio_limit,
+    ShareRatioLimit(f64) => share_ratio_limit,
+    Shared(bool) => shared,
+    StopSeedAtRatio(bool) => stop_seed_at_ratio,
+    StopSeedRatio(f64) => stop_seed_ratio,
+    SuperSeeding(bool) => super_seeding,
+    TorrentfilesLocation(RemotePath) => torrentfiles_location,
+    Upnp(bool) => upnp,
+    Utpex(bool) => utpex,
+}
+
+pub type ConfigOptions = EnumMap<ConfigOption>;
+
+impl Serialize for ConfigKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::{
+        ConfigKey, ConfigOption, ConfigOptions, CoreConfig, EncryptionLevel, EncryptionPolicy,
+    };
+    use crate::enum_map::DeserializableEnum;
+
+    #[test]
+    fn core_config() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"download_location": "/data", "listen_ports": [6881, 6891],
+            "enc_in_policy": 0, "enc_level": 1, "max_active_limit": 8, "stop_seed_ratio": 2.0,
+            "listen_random_port": null, "path_chooser_max_popup_rows": 20}"#;
+        let config: CoreConfig = serde_json::from_str(json)?;
+        assert_eq!(config.download_location, "/data");
+        assert_eq!(config.listen_ports, (6881, 6891));
+        assert_eq!(config.enc_in_policy, EncryptionPolicy::Forced);
+        assert_eq!(config.enc_level, EncryptionLevel::Rc4);
+        assert_eq!(config.other["path_chooser_max_popup_rows"], json!(20));
+        assert_eq!(
+            config.get(ConfigKey::MaxActiveLimit),
+            ConfigOption::MaxActiveLimit(8)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn config_options() -> Result<(), Box<dyn Error>> {
+        let mut options = ConfigOptions::new();
+        options.insert(ConfigOption::EncOutPolicy(EncryptionPolicy::Disabled));
+        assert_eq!(r#"{"enc_out_policy":2}"#, format!("{}", json!(options)));
+        assert_eq!(
+            ConfigOption::from_kind_value(ConfigKey::ListenPorts, json!([1, 2]))?,
+            ConfigOption::ListenPorts((1, 2))
+        );
+        assert_eq!(
+            json!([ConfigKey::MaxActiveSeeding]),
+            json!(["max_active_seeding"])
+        );
+        let options: ConfigOptions = serde_json::from_str(r#"{"dht": true, "foo": 1}"#)?;
+        assert_eq!(options.get(ConfigKey::Dht), Some(&ConfigOption::Dht(true)));
+        assert_eq!(options.unknown()["foo"], json!(1));
+        Ok(())
+    }
+
+    #[test]
+    fn config_keys() -> Result<(), Box<dyn Error>> {
+        let config = serde_json::to_value(CoreConfig::default())?;
+        for key in ConfigKey::ALL {
+            assert!(config.get(key.as_str()).is_some(), "{}", key.as_str());
+            assert_eq!(ConfigOption::kind_from_key(key.as_str()), Some(key));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Event which triggers a command of the Execute plugin.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecuteEvent {
+    /// Torrent Complete
+    Complete,
+    /// Torrent Added
+    Added,
+    /// Torrent Removed
+    Removed,
+}
+
+/// Command of the Execute plugin, deserialized from its `(id, event, command)` tuple.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExecuteCommand {
+    pub id: String,
+    pub event: ExecuteEvent,
+    pub command: String,
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::{ExecuteCommand, ExecuteEvent};
+
+    #[test]
+    fn execute_command() -> Result<(), Box<dyn Error>> {
+        let json = r#"["8e1f", "complete", "/usr/local/bin/done.sh"]"#;
+        let command: ExecuteCommand = serde_json::from_str(json)?;
+        assert_eq!(command.id, "8e1f");
+        assert_eq!(command.event, ExecuteEvent::Complete);
+        assert_eq!(command.command, "/usr/local/bin/done.sh");
+        Ok(())
+    }
+}
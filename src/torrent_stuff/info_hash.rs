@@ -0,0 +1,212 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Serialize};
+
+use crate::DelugeApiError;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Info hash of a torrent, parsed from 40 hex or 32 base32 characters (v1) or 64 hex
+/// characters (v2), and serialized as the full hex hash.
+///
+/// Deluge doesn't identify torrents by it but by their [`TorrentId`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InfoHash {
+    V1([u8; 20]),
+    V2([u8; 32]),
+}
+
+impl InfoHash {
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::V1(hash) => hash,
+            Self::V2(hash) => hash,
+        }
+    }
+
+    /// Id Deluge uses for the torrent with this hash, as long as it's the v1 hash of hybrid
+    /// torrents.
+    #[must_use]
+    pub fn to_torrent_id(&self) -> TorrentId {
+        match self {
+            Self::V1(hash) => TorrentId(*hash),
+            Self::V2(hash) => {
+                let mut id = [0; 20];
+                id.copy_from_slice(&hash[..20]);
+                TorrentId(id)
+            }
+        }
+    }
+
+    fn from_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+        if !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let mut out = [0; N];
+        for (byte, pair) in out.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+        }
+        Some(out)
+    }
+
+    fn from_base32(s: &str) -> Option<[u8; 20]> {
+        let mut out = [0; 20];
+        let mut buffer = 0_u64;
+        let mut bits = 0;
+        let mut len = 0;
+        for c in s.bytes() {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|&a| a == c.to_ascii_uppercase())?;
+            buffer = (buffer << 5) | value as u64;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                out[len] = (buffer >> bits).to_le_bytes()[0];
+                len += 1;
+            }
+        }
+        Some(out)
+    }
+}
+
+impl FromStr for InfoHash {
+    type Err = DelugeApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.len() {
+            40 => Self::from_hex(s).map(Self::V1),
+            64 => Self::from_hex(s).map(Self::V2),
+            32 => Self::from_base32(s).map(Self::V1),
+            _ => None,
+        }
+        .ok_or_else(|| DelugeApiError::InvalidInfoHash(s.to_owned()))
+    }
+}
+
+/// Lowercase hex of the full hash.
+impl fmt::Display for InfoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_bytes()
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl Serialize for InfoHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InfoHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Id of a torrent in Deluge: its v1 info hash, or its v2 info hash truncated to 20 bytes for
+/// v2-only torrents. Parsed from and serialized as 40 hex characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TorrentId([u8; 20]);
+
+impl TorrentId {
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl From<InfoHash> for TorrentId {
+    fn from(info_hash: InfoHash) -> Self {
+        info_hash.to_torrent_id()
+    }
+}
+
+impl FromStr for TorrentId {
+    type Err = DelugeApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        (s.len() == 40)
+            .then(|| InfoHash::from_hex(s))
+            .flatten()
+            .map(Self)
+            .ok_or_else(|| DelugeApiError::InvalidInfoHash(s.to_owned()))
+    }
+}
+
+impl fmt::Display for TorrentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        InfoHash::V1(self.0).fmt(f)
+    }
+}
+
+impl Serialize for TorrentId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TorrentId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::{InfoHash, TorrentId};
+
+    #[test]
+    fn info_hash() -> Result<(), Box<dyn Error>> {
+        let hex = "c9e15763f722f23e98a29decdfae341b98d53056";
+        let v1: InfoHash = hex.parse()?;
+        assert_eq!(v1.to_string(), hex);
+        let base32: InfoHash = "ZHQVOY7XELZD5GFCTXWN7LRUDOMNKMCW".parse()?;
+        assert_eq!(base32, v1);
+        let v2: InfoHash =
+            "35aefd8e55c70a58c7c1257518d80b1586c099b010d27a98140768f5372aaed4".parse()?;
+        assert!(matches!(v2, InfoHash::V2(_)));
+        assert_eq!(serde_json::from_value::<InfoHash>(json!(v2))?, v2);
+        assert_eq!(
+            json!(v2.to_torrent_id()),
+            json!("35aefd8e55c70a58c7c1257518d80b1586c099b0")
+        );
+        assert_eq!(
+            v1.to_torrent_id().to_string().parse::<TorrentId>()?,
+            v1.into()
+        );
+        assert!(v2.to_string().parse::<TorrentId>().is_err());
+        assert!("c9e15763f722f23e98a29decdfae341b98d5305"
+            .parse::<InfoHash>()
+            .is_err());
+        assert!("g9e15763f722f23e98a29decdfae341b98d53056"
+            .parse::<InfoHash>()
+            .is_err());
+        assert!("ZHQVOY7XELZD5GFCTXWN7LRUDOMNKMC1"
+            .parse::<InfoHash>()
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use super::RemotePath;
+
+/// Options of a label of the Label plugin, the defaults are the ones used by the plugin.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct LabelOptions {
+    /// Whether the `max_*` fields are applied to the torrents of the label.
+    pub apply_max: bool,
+    /// In KiB/s, `-1` means unlimited.
+    pub max_download_speed: f64,
+    /// In KiB/s, `-1` means unlimited.
+    pub max_upload_speed: f64,
+    pub max_connections: i64,
+    pub max_upload_slots: i64,
+    pub prioritize_first_last: bool,
+    /// Whether the queue fields are applied to the torrents of the label.
+    pub apply_queue: bool,
+    pub is_auto_managed: bool,
+    pub stop_at_ratio: bool,
+    pub stop_ratio: f64,
+    pub remove_at_ratio: bool,
+    /// Whether `move_completed` and `move_completed_to` are applied.
+    pub apply_move_completed: bool,
+    pub move_completed: bool,
+    #[serde(rename = "move_completed_path")]
+    pub move_completed_to: RemotePath,
+    /// Whether new torrents with any of `auto_add_trackers` get the label.
+    pub auto_add: bool,
+    pub auto_add_trackers: Vec<String>,
+}
+
+impl Default for LabelOptions {
+    fn default() -> Self {
+        Self {
+            apply_max: false,
+            max_download_speed: -1.0,
+            max_upload_speed: -1.0,
+            max_connections: -1,
+            max_upload_slots: -1,
+            prioritize_first_last: false,
+            apply_queue: false,
+            is_auto_managed: false,
+            stop_at_ratio: false,
+            stop_ratio: 2.0,
+            remove_at_ratio: false,
+            apply_move_completed: false,
+            move_completed: false,
+            move_completed_to: RemotePath::default(),
+            auto_add: false,
+            auto_add_trackers: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::LabelOptions;
+
+    #[test]
+    fn label_options() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"apply_max": true, "max_download_speed": 512.0, "max_upload_speed": -1,
+            "move_completed_path": "/data/tv", "auto_add_trackers": ["tracker.example.org"]}"#;
+        let options: LabelOptions = serde_json::from_str(json)?;
+        assert!(options.apply_max);
+        assert!((options.max_download_speed - 512.0).abs() < f64::EPSILON);
+        assert_eq!(options.max_connections, -1);
+        assert_eq!(options.move_completed_to, "/data/tv");
+        assert_eq!(options.auto_add_trackers, ["tracker.example.org"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use std::{fmt, str::FromStr};
+
+use reqwest::Url;
+use serde::{de, Deserialize, Serialize};
+
+use crate::DelugeApiError;
+
+use super::{InfoHash, TorrentId};
+
+/// Magnet link (BEP 9, and BEP 52 for v2 hashes). The `xt`, `dn`, `tr`, `ws` and `xl`
+/// parameters are parsed, the others are kept as they are in [`MagnetUri::other`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MagnetUri {
+    /// Exact topics, at most one v1 and one v2 hash.
+    pub info_hashes: Vec<InfoHash>,
+    pub display_name: Option<String>,
+    pub trackers: Vec<Url>,
+    pub web_seeds: Vec<Url>,
+    pub length: Option<u64>,
+    /// Other parameters in their original order, such as `x.pe` peers or `so` file selection.
+    pub other: Vec<(String, String)>,
+}
+
+impl MagnetUri {
+    #[must_use]
+    pub fn new(info_hash: InfoHash) -> Self {
+        Self {
+            info_hashes: vec![info_hash],
+            display_name: None,
+            trackers: Vec::new(),
+            web_seeds: Vec::new(),
+            length: None,
+            other: Vec::new(),
+        }
+    }
+
+    /// The v1 hash if there's one, else the v2 hash.
+    #[must_use]
+    pub fn info_hash(&self) -> Option<InfoHash> {
+        self.info_hashes
+            .iter()
+            .find(|hash| matches!(hash, InfoHash::V1(_)))
+            .or_else(|| self.info_hashes.first())
+            .copied()
+    }
+
+    /// Id Deluge will identify the torrent by.
+    #[must_use]
+    pub fn torrent_id(&self) -> Option<TorrentId> {
+        self.info_hash().map(TorrentId::from)
+    }
+
+    pub fn with_info_hash(&mut self, info_hash: InfoHash) -> &mut Self {
+        self.info_hashes.push(info_hash);
+        self
+    }
+    pub fn with_name(&mut self, name: String) -> &mut Self {
+        self.display_name = name.into();
+        self
+    }
+    pub fn with_tracker(&mut self, tracker: Url) -> &mut Self {
+        self.trackers.push(tracker);
+        self
+    }
+    pub fn with_web_seed(&mut self, web_seed: Url) -> &mut Self {
+        self.web_seeds.push(web_seed);
+        self
+    }
+    pub fn with_length(&mut self, length: u64) -> &mut Self {
+        self.length = length.into();
+        self
+    }
+}
+
+/// Matches `key` and its numbered forms, e.g. `tr.1`.
+fn is_param(key: &str, name: &str) -> bool {
+    key.strip_prefix(name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+impl FromStr for MagnetUri {
+    type Err = DelugeApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DelugeApiError::InvalidMagnetUri(s.to_owned());
+        let url = Url::parse(s).map_err(|_| invalid())?;
+        if url.scheme() != "magnet" {
+            return Err(invalid());
+        }
+        let mut magnet = Self {
+            info_hashes: Vec::new(),
+            display_name: None,
+            trackers: Vec::new(),
+            web_seeds: Vec::new(),
+            length: None,
+            other: Vec::new(),
+        };
+        for (key, value) in url.query_pairs() {
+            if is_param(&key, "xt") {
+                let hash = if let Some(hash) = value.strip_prefix("urn:btih:") {
+                    hash.parse()
+                        .ok()
+                        .filter(|hash| matches!(hash, InfoHash::V1(_)))
+                } else if let Some(hash) = value.strip_prefix("urn:btmh:1220") {
+                    hash.parse()
+                        .ok()
+                        .filter(|hash| matches!(hash, InfoHash::V2(_)))
+                } else {
+                    magnet.other.push((key.into_owned(), value.into_owned()));
+                    continue;
+                };
+                magnet.info_hashes.push(hash.ok_or_else(invalid)?);
+            } else if is_param(&key, "dn") {
+                magnet.display_name = Some(value.into_owned());
+            } else if is_param(&key, "tr") {
+                magnet
+                    .trackers
+                    .push(Url::parse(&value).map_err(|_| invalid())?);
+            } else if is_param(&key, "ws") {
+                magnet
+                    .web_seeds
+                    .push(Url::parse(&value).map_err(|_| invalid())?);
+            } else if key == "xl" {
+                magnet.length = Some(value.parse().map_err(|_| invalid())?);
+            } else {
+                magnet.other.push((key.into_owned(), value.into_owned()));
+            }
+        }
+        if magnet.info_hashes.is_empty() {
+            return Err(invalid());
+        }
+        Ok(magnet)
+    }
+}
+
+impl fmt::Display for MagnetUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let topics = self
+            .info_hashes
+            .iter()
+            .map(|hash| match hash {
+                InfoHash::V1(_) => format!("xt=urn:btih:{hash}"),
+                InfoHash::V2(_) => format!("xt=urn:btmh:1220{hash}"),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        let mut url = Url::parse(&format!("magnet:?{topics}")).map_err(|_| fmt::Error)?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(name) = &self.display_name {
+                query.append_pair("dn", name);
+            }
+            if let Some(length) = self.length {
+                query.append_pair("xl", &length.to_string());
+            }
+            for tracker in &self.trackers {
+                query.append_pair("tr", tracker.as_str());
+            }
+            for web_seed in &self.web_seeds {
+                query.append_pair("ws", web_seed.as_str());
+            }
+            query.extend_pairs(&self.other);
+        }
+        f.write_str(url.as_str())
+    }
+}
+
+impl Serialize for MagnetUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MagnetUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::Url;
+
+    use super::MagnetUri;
+    use crate::{torrent_stuff::InfoHash, DelugeApiError};
+
+    #[test]
+    fn parse() -> Result<(), DelugeApiError> {
+        let uri = "magnet:?xt=urn:btih:ZHQVOY7XELZD5GFCTXWN7LRUDOMNKMCW&dn=Some+Name&tr=udp%3A%2F%2Ft.example%3A80&tr.1=http://t2.example/announce&ws=http://seed.example/&xl=1234&so=0";
+        let magnet: MagnetUri = uri.parse()?;
+        assert_eq!(
+            magnet.info_hash(),
+            Some("c9e15763f722f23e98a29decdfae341b98d53056".parse()?)
+        );
+        assert_eq!(magnet.display_name.as_deref(), Some("Some Name"));
+        assert_eq!(magnet.trackers.len(), 2);
+        assert_eq!(magnet.trackers[0].as_str(), "udp://t.example:80");
+        assert_eq!(magnet.web_seeds.len(), 1);
+        assert_eq!(magnet.length, Some(1234));
+        assert_eq!(magnet.other, [("so".to_owned(), "0".to_owned())]);
+        assert!("magnet:?dn=a".parse::<MagnetUri>().is_err());
+        assert!("magnet:?xt=urn:btih:abc".parse::<MagnetUri>().is_err());
+        assert!("http://a?xt=urn:btih:ZHQVOY7XELZD5GFCTXWN7LRUDOMNKMCW"
+            .parse::<MagnetUri>()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn build() -> Result<(), Box<dyn std::error::Error>> {
+        let v2: InfoHash =
+            "35aefd8e55c70a58c7c1257518d80b1586c099b010d27a98140768f5372aaed4".parse()?;
+        let mut magnet = MagnetUri::new(v2);
+        magnet
+            .with_name("a b".to_owned())
+            .with_tracker(Url::parse("http://t.example/announce")?);
+        let uri = magnet.to_string();
+        assert_eq!(
+            uri,
+            "magnet:?xt=urn:btmh:122035aefd8e55c70a58c7c1257518d80b1586c099b010d27a98140768f5372aaed4&dn=a+b&tr=http%3A%2F%2Ft.example%2Fannounce"
+        );
+        assert_eq!(uri.parse::<MagnetUri>()?, magnet);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), DelugeApiError> {
+        let uri = "magnet:?xt=urn:btih:c9e15763f722f23e98a29decdfae341b98d53056&dn=a&x.pe=10.0.0.1%3A6881&so=0%2C2-4&kt=linux+iso";
+        let magnet: MagnetUri = uri.parse()?;
+        assert_eq!(
+            serde_json::to_value(&magnet).ok(),
+            Some(serde_json::json!(uri))
+        );
+        assert_eq!(magnet.to_string().parse::<MagnetUri>()?, magnet);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::Secret;
+
+/// Configuration of the daemon side of the Notifications plugin, which only sends emails.
+/// Popup, blink and sound notifications are configured in each GTK client.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotificationsConfig {
+    pub smtp_enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_user: String,
+    pub smtp_pass: Secret,
+    pub smtp_from: String,
+    pub smtp_tls: bool,
+    pub smtp_recipients: Vec<String>,
+    /// Event names (e.g. `TorrentFinishedEvent`) by kind of notification, only `email`
+    /// is used by the daemon.
+    pub subscriptions: HashMap<String, Vec<String>>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            smtp_enabled: false,
+            smtp_host: String::new(),
+            smtp_port: 25,
+            smtp_user: String::new(),
+            smtp_pass: Secret::default(),
+            smtp_from: String::new(),
+            smtp_tls: false,
+            smtp_recipients: Vec::new(),
+            subscriptions: HashMap::from([("email".to_owned(), Vec::new())]),
+        }
+    }
+}
+
+/// Configuration of the Extractor plugin.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExtractorConfig {
+    /// Empty to extract in the download folder of the torrent.
+    pub extract_path: String,
+    /// Extract into a folder named after the torrent.
+    pub use_name_folder: bool,
+}
+
+impl Default for ExtractorConfig {
+    fn default() -> Self {
+        Self {
+            extract_path: String::new(),
+            use_name_folder: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::NotificationsConfig;
+
+    #[test]
+    fn notifications_config() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"smtp_enabled": true, "smtp_host": "smtp.example.org", "smtp_port": 587,
+            "smtp_user": "deluge", "smtp_pass": "hunter2", "smtp_from": "deluge@example.org",
+            "smtp_tls": true, "smtp_recipients": ["ops@example.org"],
+            "subscriptions": {"email": ["TorrentFinishedEvent"]}}"#;
+        let config: NotificationsConfig = serde_json::from_str(json)?;
+        assert_eq!(config.smtp_port, 587);
+        assert_eq!(config.subscriptions["email"], ["TorrentFinishedEvent"]);
+        assert_eq!(config.smtp_pass.expose(), "hunter2");
+        assert!(!format!("{config:?}").contains("hunter2"));
+        Ok(())
+    }
+}
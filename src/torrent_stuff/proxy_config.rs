@@ -0,0 +1,146 @@
+use std::fmt;
+
+use serde::{de, Deserialize, Serialize};
+
+/// String which isn't shown by [`fmt::Debug`] nor [`fmt::Display`], such as a password.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(***)")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ProxyType {
+    #[default]
+    None = 0,
+    Socks4 = 1,
+    Socks5 = 2,
+    Socks5Auth = 3,
+    HTTP = 4,
+    HTTPAuth = 5,
+    I2P = 6,
+}
+
+impl Serialize for ProxyType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProxyType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Socks4),
+            2 => Ok(Self::Socks5),
+            3 => Ok(Self::Socks5Auth),
+            4 => Ok(Self::HTTP),
+            5 => Ok(Self::HTTPAuth),
+            6 => Ok(Self::I2P),
+            other => Err(de::Error::custom(format!("invalid proxy type {other}"))),
+        }
+    }
+}
+
+/// Proxy settings, the `proxy` key of `core.conf` and the result of `core.get_proxy`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ProxyConfig {
+    #[serde(rename = "type")]
+    pub proxy_type: ProxyType,
+    pub hostname: String,
+    pub port: u16,
+    pub username: String,
+    pub password: Secret,
+    /// Resolve hostnames through the proxy.
+    pub proxy_hostnames: bool,
+    pub proxy_peer_connections: bool,
+    pub proxy_tracker_connections: bool,
+    /// Don't fall back to direct connections.
+    pub force_proxy: bool,
+    pub anonymous_mode: bool,
+}
+
+impl ProxyConfig {
+    /// Every connection (peers, trackers and hostname lookups) goes through the proxy, with no
+    /// fallback to direct connections.
+    #[must_use]
+    pub fn is_enforced(&self) -> bool {
+        self.proxy_type != ProxyType::None
+            && self.proxy_hostnames
+            && self.proxy_peer_connections
+            && self.proxy_tracker_connections
+            && self.force_proxy
+    }
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            proxy_type: ProxyType::None,
+            hostname: String::new(),
+            port: 8080,
+            username: String::new(),
+            password: Secret::default(),
+            proxy_hostnames: true,
+            proxy_peer_connections: true,
+            proxy_tracker_connections: true,
+            force_proxy: false,
+            anonymous_mode: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::{ProxyConfig, ProxyType};
+
+    #[test]
+    fn proxy_config() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"type": 3, "hostname": "10.8.0.1", "port": 1080, "username": "vpn",
+            "password": "hunter2", "proxy_hostnames": true, "proxy_peer_connections": true,
+            "proxy_tracker_connections": true, "force_proxy": true, "anonymous_mode": false}"#;
+        let proxy: ProxyConfig = serde_json::from_str(json)?;
+        assert_eq!(proxy.proxy_type, ProxyType::Socks5Auth);
+        assert!(proxy.is_enforced());
+        assert_eq!(proxy.password.expose(), "hunter2");
+        assert!(!format!("{proxy:?}").contains("hunter2"));
+        assert_eq!(json!(proxy)["password"], json!("hunter2"));
+        Ok(())
+    }
+}
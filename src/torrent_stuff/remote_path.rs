@@ -0,0 +1,269 @@
+use std::fmt;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// Path in the daemon's or deluge-web's filesystem, which isn't necessarily the local one
+/// (another OS, a container with other mounts...), so it's kept as the string they use.
+///
+/// Paths starting with a drive letter or `\\` are treated as Windows paths: both `\` and `/`
+/// separate components and [`PathMappings`] ignores ASCII case when matching their prefixes.
+/// Equality, hashing and ordering compare the strings as they are.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RemotePath(String);
+
+impl RemotePath {
+    #[must_use]
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(path.into())
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn is_windows(&self) -> bool {
+        let bytes = self.0.as_bytes();
+        self.0.starts_with(r"\\")
+            || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+    }
+
+    #[must_use]
+    pub fn separator(&self) -> char {
+        if self.is_windows() {
+            '\\'
+        } else {
+            '/'
+        }
+    }
+
+    /// Non-empty components, without the root.
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        let windows = self.is_windows();
+        self.0
+            .split(move |c| c == '/' || (windows && c == '\\'))
+            .filter(|component| !component.is_empty())
+    }
+
+    /// Appends the components of `path` with this path's separator.
+    #[must_use]
+    pub fn join(&self, path: &str) -> Self {
+        self.join_components(Self::new(path).components())
+    }
+
+    fn join_components<'a>(&self, components: impl IntoIterator<Item = &'a str>) -> Self {
+        let windows = self.is_windows();
+        let separator = self.separator();
+        let mut out = self
+            .0
+            .trim_end_matches(|c| c == '/' || (windows && c == '\\'))
+            .to_owned();
+        // Keep the root, `/` or `D:\`.
+        if out.is_empty() || (windows && out.len() == 2) {
+            out.push(separator);
+        }
+        for component in components {
+            if !out.ends_with(separator) {
+                out.push(separator);
+            }
+            out.push_str(component);
+        }
+        Self(out)
+    }
+
+    /// Components left after `base`, or `None` if `base` isn't a prefix of this path.
+    fn strip_prefix<'a>(&'a self, base: &Self) -> Option<Vec<&'a str>> {
+        let windows = base.is_windows();
+        let mut components = self.components();
+        for expected in base.components() {
+            let component = components.next()?;
+            let same = if windows {
+                component.eq_ignore_ascii_case(expected)
+            } else {
+                component == expected
+            };
+            if !same {
+                return None;
+            }
+        }
+        Some(components.collect())
+    }
+}
+
+impl fmt::Display for RemotePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for RemotePath {
+    fn from(path: String) -> Self {
+        Self(path)
+    }
+}
+
+impl From<&str> for RemotePath {
+    fn from(path: &str) -> Self {
+        Self::new(path)
+    }
+}
+
+impl AsRef<str> for RemotePath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for RemotePath {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for RemotePath {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Local folder and the daemon's path for it, e.g. `/mnt/torrents` mounted as `/downloads`
+/// in the daemon's container.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathMapping {
+    pub local: Utf8PathBuf,
+    pub remote: RemotePath,
+}
+
+/// Translates paths between the local filesystem and the daemon's one, the longest matching
+/// prefix wins and paths without a mapping are kept as is.
+///
+/// The clients take and return the daemon's paths as they are, callers translate them with
+/// [`PathMappings::to_remote`] and [`PathMappings::to_local`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PathMappings(Vec<PathMapping>);
+
+impl PathMappings {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn with_mapping(&mut self, local: Utf8PathBuf, remote: RemotePath) -> &mut Self {
+        self.0.push(PathMapping { local, remote });
+        self
+    }
+
+    #[must_use]
+    pub fn mappings(&self) -> &[PathMapping] {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn to_remote(&self, path: &Utf8Path) -> RemotePath {
+        self.0
+            .iter()
+            .filter_map(|mapping| {
+                let rest = path.strip_prefix(&mapping.local).ok()?;
+                Some((mapping.local.components().count(), &mapping.remote, rest))
+            })
+            .max_by_key(|(depth, ..)| *depth)
+            .map_or_else(
+                || RemotePath::new(path.as_str()),
+                |(_, remote, rest)| {
+                    remote.join_components(rest.components().map(|component| component.as_str()))
+                },
+            )
+    }
+
+    #[must_use]
+    pub fn to_local(&self, path: &RemotePath) -> Utf8PathBuf {
+        self.0
+            .iter()
+            .filter_map(|mapping| {
+                let rest = path.strip_prefix(&mapping.remote)?;
+                Some((mapping.remote.components().count(), &mapping.local, rest))
+            })
+            .max_by_key(|(depth, ..)| *depth)
+            .map_or_else(
+                || Utf8PathBuf::from(path.as_str()),
+                |(_, local, rest)| rest.into_iter().fold(local.clone(), |path, c| path.join(c)),
+            )
+    }
+}
+
+impl FromIterator<PathMapping> for PathMappings {
+    fn from_iter<T: IntoIterator<Item = PathMapping>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use camino::{Utf8Path, Utf8PathBuf};
+
+    use super::{PathMapping, PathMappings, RemotePath};
+
+    #[test]
+    fn remote_path() {
+        let windows = RemotePath::from(r"D:\Torrents\");
+        assert!(windows.is_windows());
+        assert_eq!(windows.join("tv/show"), r"D:\Torrents\tv\show");
+        assert_eq!(RemotePath::from("/").join("data"), "/data");
+        assert_eq!(RemotePath::from("/data/").join(""), "/data");
+        assert_eq!(RemotePath::from(r"D:\").join(""), r"D:\");
+        assert_eq!(RemotePath::from("D:/").join("tv"), r"D:\tv");
+        assert_eq!(RemotePath::from(r"D:\Torrents/").join(""), r"D:\Torrents");
+        assert!(!RemotePath::from(r"/data/a\b").is_windows());
+        assert_eq!(
+            RemotePath::from(r"/data/a\b")
+                .components()
+                .collect::<Vec<_>>(),
+            ["data", r"a\b"]
+        );
+    }
+
+    #[test]
+    fn mappings() {
+        let mut mappings = PathMappings::new();
+        mappings
+            .with_mapping("/mnt/torrents".into(), "/downloads".into())
+            .with_mapping("/mnt/torrents/tv".into(), r"D:\TV".into());
+
+        assert_eq!(
+            mappings.to_remote(Utf8Path::new("/mnt/torrents/movies/a.mkv")),
+            "/downloads/movies/a.mkv"
+        );
+        assert_eq!(
+            mappings.to_remote(Utf8Path::new("/mnt/torrents/tv/show")),
+            r"D:\TV\show"
+        );
+        assert_eq!(
+            PathMappings::from_iter([PathMapping {
+                local: "/mnt/d".into(),
+                remote: r"D:\".into(),
+            }])
+            .to_remote(Utf8Path::new("/mnt/d")),
+            r"D:\"
+        );
+        assert_eq!(
+            mappings.to_remote(Utf8Path::new("/mnt/torrents2")),
+            "/mnt/torrents2"
+        );
+        assert_eq!(
+            mappings.to_local(&r"d:\tv\show/s01".into()),
+            Utf8PathBuf::from("/mnt/torrents/tv/show/s01")
+        );
+        assert_eq!(
+            mappings.to_local(&"/downloads".into()),
+            Utf8PathBuf::from("/mnt/torrents")
+        );
+        assert_eq!(
+            mappings.to_local(&"/downloadsx/a".into()),
+            Utf8PathBuf::from("/downloadsx/a")
+        );
+    }
+}
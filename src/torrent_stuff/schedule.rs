@@ -0,0 +1,286 @@
+use std::{fmt, ops::Range};
+
+use serde::{de, Deserialize, Serialize};
+
+use crate::DelugeApiError;
+
+/// State of an hour of the Scheduler plugin.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[repr(u8)]
+pub enum ScheduleState {
+    /// Normal speeds.
+    #[default]
+    Green = 0,
+    /// Limited to the `low_*` settings of [`SchedulerConfig`].
+    Yellow = 1,
+    /// Paused.
+    Red = 2,
+}
+
+impl ScheduleState {
+    const fn symbol(self) -> char {
+        match self {
+            Self::Green => '.',
+            Self::Yellow => 'L',
+            Self::Red => 'P',
+        }
+    }
+}
+
+impl Serialize for ScheduleState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScheduleState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(Self::Green),
+            1 => Ok(Self::Yellow),
+            2 => Ok(Self::Red),
+            other => Err(de::Error::custom(format!("invalid schedule state {other}"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+#[repr(u8)]
+pub enum Weekday {
+    #[strum(serialize = "Mon")]
+    Monday = 0,
+    #[strum(serialize = "Tue")]
+    Tuesday = 1,
+    #[strum(serialize = "Wed")]
+    Wednesday = 2,
+    #[strum(serialize = "Thu")]
+    Thursday = 3,
+    #[strum(serialize = "Fri")]
+    Friday = 4,
+    #[strum(serialize = "Sat")]
+    Saturday = 5,
+    #[strum(serialize = "Sun")]
+    Sunday = 6,
+}
+
+impl Weekday {
+    pub const ALL: [Self; 7] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+        Self::Saturday,
+        Self::Sunday,
+    ];
+    pub const WEEKDAYS: [Self; 5] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+    ];
+    pub const WEEKEND: [Self; 2] = [Self::Saturday, Self::Sunday];
+}
+
+/// Weekly grid of the Scheduler plugin, one [`ScheduleState`] per hour of each day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WeeklySchedule([[ScheduleState; 24]; 7]);
+
+impl WeeklySchedule {
+    #[must_use]
+    pub const fn filled(state: ScheduleState) -> Self {
+        Self([[state; 24]; 7])
+    }
+
+    /// Returns `None` if `hour` isn't less than 24.
+    #[must_use]
+    pub const fn get(&self, day: Weekday, hour: u8) -> Option<ScheduleState> {
+        if hour < 24 {
+            Some(self.0[day as usize][hour as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Fails if `hour` isn't less than 24.
+    pub fn set(
+        &mut self,
+        day: Weekday,
+        hour: u8,
+        state: ScheduleState,
+    ) -> Result<&mut Self, DelugeApiError> {
+        self.set_range([day], hour..hour.saturating_add(1), state)
+    }
+
+    /// Sets `hours` (e.g. `9..17` for 09:00-17:00) of every day in `days`. Fails if the range
+    /// is reversed or ends after 24.
+    pub fn set_range<I>(
+        &mut self,
+        days: I,
+        hours: Range<u8>,
+        state: ScheduleState,
+    ) -> Result<&mut Self, DelugeApiError>
+    where
+        I: IntoIterator<Item = Weekday>,
+    {
+        if hours.start > hours.end || hours.end > 24 {
+            return Err(DelugeApiError::InvalidHours {
+                start: hours.start,
+                end: hours.end,
+            });
+        }
+        for day in days {
+            self.0[day as usize][usize::from(hours.start)..usize::from(hours.end)].fill(state);
+        }
+        Ok(self)
+    }
+}
+
+/// Deluge stores the grid by hour, then weekday.
+impl Serialize for WeeklySchedule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let grid: Vec<[ScheduleState; 7]> = (0..24)
+            .map(|hour| Weekday::ALL.map(|day| self.0[day as usize][hour]))
+            .collect();
+        grid.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WeeklySchedule {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let grid = Vec::<[ScheduleState; 7]>::deserialize(deserializer)?;
+        if grid.len() != 24 {
+            return Err(de::Error::invalid_length(grid.len(), &"24 hours"));
+        }
+        let mut schedule = Self::default();
+        for (hour, states) in grid.into_iter().enumerate() {
+            for (day, state) in states.into_iter().enumerate() {
+                schedule.0[day][hour] = state;
+            }
+        }
+        Ok(schedule)
+    }
+}
+
+/// Renders the grid as text, with `.` for [`ScheduleState::Green`], `L` for
+/// [`ScheduleState::Yellow`] and `P` for [`ScheduleState::Red`].
+impl fmt::Display for WeeklySchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "   ")?;
+        for hour in 0..24 {
+            write!(f, " {hour:02}")?;
+        }
+        for day in Weekday::ALL {
+            write!(f, "\n{day}")?;
+            for state in self.0[day as usize] {
+                write!(f, "  {}", state.symbol())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration of the Scheduler plugin.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SchedulerConfig {
+    /// Download speed in KiB/s while [`ScheduleState::Yellow`], `-1` means unlimited.
+    pub low_down: f64,
+    /// Upload speed in KiB/s while [`ScheduleState::Yellow`], `-1` means unlimited.
+    pub low_up: f64,
+    pub low_active: i64,
+    pub low_active_down: i64,
+    pub low_active_up: i64,
+    #[serde(rename = "button_state")]
+    pub schedule: WeeklySchedule,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            low_down: -1.0,
+            low_up: -1.0,
+            low_active: -1,
+            low_active_down: -1,
+            low_active_up: -1,
+            schedule: WeeklySchedule::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::{ScheduleState, Weekday, WeeklySchedule};
+
+    #[test]
+    fn schedule_round_trip() -> Result<(), Box<dyn Error>> {
+        let mut schedule = WeeklySchedule::default();
+        schedule
+            .set_range(Weekday::WEEKEND, 0..24, ScheduleState::Red)?
+            .set(Weekday::Tuesday, 23, ScheduleState::Yellow)?;
+        let value = json!(schedule);
+        assert_eq!(value.as_array().map(Vec::len), Some(24));
+        assert_eq!(value[23], json!([0, 1, 0, 0, 0, 2, 2]));
+        let parsed: WeeklySchedule = serde_json::from_value(value)?;
+        assert_eq!(parsed, schedule);
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_range() -> Result<(), Box<dyn Error>> {
+        let mut schedule = WeeklySchedule::default();
+        assert_eq!(
+            schedule.get(Weekday::Monday, 23),
+            Some(ScheduleState::Green)
+        );
+        assert_eq!(schedule.get(Weekday::Monday, 24), None);
+        assert!(schedule
+            .set(Weekday::Monday, 24, ScheduleState::Red)
+            .is_err());
+        assert!(schedule
+            .set(Weekday::Monday, u8::MAX, ScheduleState::Red)
+            .is_err());
+        assert!(schedule
+            .set_range(Weekday::ALL, 20..25, ScheduleState::Red)
+            .is_err());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 17..9;
+        assert!(schedule
+            .set_range(Weekday::WEEKDAYS, reversed, ScheduleState::Red)
+            .is_err());
+        assert_eq!(schedule, WeeklySchedule::default());
+        schedule.set_range(Weekday::ALL, 5..5, ScheduleState::Red)?;
+        assert_eq!(schedule, WeeklySchedule::default());
+        Ok(())
+    }
+
+    #[test]
+    fn render() -> Result<(), Box<dyn Error>> {
+        let mut schedule = WeeklySchedule::default();
+        schedule.set_range([Weekday::Monday], 22..24, ScheduleState::Yellow)?;
+        let text = schedule.to_string();
+        let mut lines = text.lines();
+        assert!(lines.next().is_some_and(|l| l.ends_with("22 23")));
+        assert!(lines
+            .next()
+            .is_some_and(|l| l.starts_with("Mon  .") && l.ends_with("L  L")));
+        assert_eq!(lines.count(), 6);
+        Ok(())
+    }
+}
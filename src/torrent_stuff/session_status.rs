@@ -0,0 +1,324 @@
+use std::{collections::HashMap, convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Kind of value of a [`SessionStatusKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatusKind {
+    /// Bytes per second, as a float.
+    Rate,
+    /// Integer counter or gauge.
+    Counter,
+    Flag,
+}
+
+/// Key of `core.get_session_status`.
+///
+/// Besides the names Deluge keeps for compatibility, any libtorrent session counter
+/// (e.g. `net.recv_redundant_bytes`) can be requested through [`SessionStatusKey::new`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SessionStatusKey {
+    PayloadDownloadRate,
+    PayloadUploadRate,
+    DownloadRate,
+    UploadRate,
+    DhtDownloadRate,
+    DhtUploadRate,
+    IpOverheadDownloadRate,
+    IpOverheadUploadRate,
+    TrackerDownloadRate,
+    TrackerUploadRate,
+    HasIncomingConnections,
+    NumPeers,
+    NumUnchoked,
+    DhtNodes,
+    DhtNodeCache,
+    DhtTorrents,
+    TotalDownload,
+    TotalUpload,
+    TotalPayloadDownload,
+    TotalPayloadUpload,
+    DiskNumBlocksRead,
+    DiskNumBlocksWritten,
+    DiskNumReadOps,
+    DiskNumWriteOps,
+    DiskBlocksInUse,
+    DiskQueuedJobs,
+    DiskReadCacheBlocks,
+    DiskWriteCacheBlocks,
+    DiskPinnedBlocks,
+    /// Any other libtorrent counter, built with [`SessionStatusKey::new`].
+    Other(OtherKey),
+}
+
+/// Name of a [`SessionStatusKey::Other`], never one of the other variants.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OtherKey(String);
+
+impl OtherKey {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl SessionStatusKey {
+    /// Key named `key`, [`SessionStatusKey::Other`] only if it isn't one of the other
+    /// variants.
+    #[must_use]
+    pub fn new(key: &str) -> Self {
+        key.parse().unwrap_or_else(|e| match e {})
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::PayloadDownloadRate => "payload_download_rate",
+            Self::PayloadUploadRate => "payload_upload_rate",
+            Self::DownloadRate => "download_rate",
+            Self::UploadRate => "upload_rate",
+            Self::DhtDownloadRate => "dht_download_rate",
+            Self::DhtUploadRate => "dht_upload_rate",
+            Self::IpOverheadDownloadRate => "ip_overhead_download_rate",
+            Self::IpOverheadUploadRate => "ip_overhead_upload_rate",
+            Self::TrackerDownloadRate => "tracker_download_rate",
+            Self::TrackerUploadRate => "tracker_upload_rate",
+            Self::HasIncomingConnections => "has_incoming_connections",
+            Self::NumPeers => "num_peers",
+            Self::NumUnchoked => "num_unchoked",
+            Self::DhtNodes => "dht_nodes",
+            Self::DhtNodeCache => "dht_node_cache",
+            Self::DhtTorrents => "dht_torrents",
+            Self::TotalDownload => "total_download",
+            Self::TotalUpload => "total_upload",
+            Self::TotalPayloadDownload => "total_payload_download",
+            Self::TotalPayloadUpload => "total_payload_upload",
+            Self::DiskNumBlocksRead => "disk.num_blocks_read",
+            Self::DiskNumBlocksWritten => "disk.num_blocks_written",
+            Self::DiskNumReadOps => "disk.num_read_ops",
+            Self::DiskNumWriteOps => "disk.num_write_ops",
+            Self::DiskBlocksInUse => "disk.disk_blocks_in_use",
+            Self::DiskQueuedJobs => "disk.queued_disk_jobs",
+            Self::DiskReadCacheBlocks => "disk.read_cache_blocks",
+            Self::DiskWriteCacheBlocks => "disk.write_cache_blocks",
+            Self::DiskPinnedBlocks => "disk.pinned_blocks",
+            Self::Other(key) => key.as_str(),
+        }
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> SessionStatusKind {
+        match self {
+            Self::PayloadDownloadRate
+            | Self::PayloadUploadRate
+            | Self::DownloadRate
+            | Self::UploadRate
+            | Self::DhtDownloadRate
+            | Self::DhtUploadRate
+            | Self::IpOverheadDownloadRate
+            | Self::IpOverheadUploadRate
+            | Self::TrackerDownloadRate
+            | Self::TrackerUploadRate => SessionStatusKind::Rate,
+            Self::HasIncomingConnections => SessionStatusKind::Flag,
+            _ => SessionStatusKind::Counter,
+        }
+    }
+}
+
+impl FromStr for SessionStatusKey {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "payload_download_rate" => Self::PayloadDownloadRate,
+            "payload_upload_rate" => Self::PayloadUploadRate,
+            "download_rate" => Self::DownloadRate,
+            "upload_rate" => Self::UploadRate,
+            "dht_download_rate" => Self::DhtDownloadRate,
+            "dht_upload_rate" => Self::DhtUploadRate,
+            "ip_overhead_download_rate" => Self::IpOverheadDownloadRate,
+            "ip_overhead_upload_rate" => Self::IpOverheadUploadRate,
+            "tracker_download_rate" => Self::TrackerDownloadRate,
+            "tracker_upload_rate" => Self::TrackerUploadRate,
+            "has_incoming_connections" => Self::HasIncomingConnections,
+            "num_peers" => Self::NumPeers,
+            "num_unchoked" => Self::NumUnchoked,
+            "dht_nodes" => Self::DhtNodes,
+            "dht_node_cache" => Self::DhtNodeCache,
+            "dht_torrents" => Self::DhtTorrents,
+            "total_download" => Self::TotalDownload,
+            "total_upload" => Self::TotalUpload,
+            "total_payload_download" => Self::TotalPayloadDownload,
+            "total_payload_upload" => Self::TotalPayloadUpload,
+            "disk.num_blocks_read" => Self::DiskNumBlocksRead,
+            "disk.num_blocks_written" => Self::DiskNumBlocksWritten,
+            "disk.num_read_ops" => Self::DiskNumReadOps,
+            "disk.num_write_ops" => Self::DiskNumWriteOps,
+            "disk.disk_blocks_in_use" => Self::DiskBlocksInUse,
+            "disk.queued_disk_jobs" => Self::DiskQueuedJobs,
+            "disk.read_cache_blocks" => Self::DiskReadCacheBlocks,
+            "disk.write_cache_blocks" => Self::DiskWriteCacheBlocks,
+            "disk.pinned_blocks" => Self::DiskPinnedBlocks,
+            other => Self::Other(OtherKey(other.to_owned())),
+        })
+    }
+}
+
+impl fmt::Display for SessionStatusKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for SessionStatusKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionStatusValue {
+    Rate(f64),
+    Counter(i64),
+    Flag(bool),
+    /// Value that doesn't fit the key's kind, as Deluge returned it.
+    Raw(Value),
+}
+
+impl SessionStatusValue {
+    /// Other counters are only integers, floats aren't truncated as their kind is unknown.
+    fn parse(key: &SessionStatusKey, value: Value) -> Self {
+        let parsed = match key {
+            SessionStatusKey::Other(_) => value.as_i64().map(Self::Counter),
+            _ => Self::parse_kind(key.kind(), &value),
+        };
+        parsed.unwrap_or(Self::Raw(value))
+    }
+
+    fn parse_kind(kind: SessionStatusKind, value: &Value) -> Option<Self> {
+        match kind {
+            SessionStatusKind::Rate => value.as_f64().map(Self::Rate),
+            #[allow(clippy::cast_possible_truncation)]
+            SessionStatusKind::Counter => value
+                .as_i64()
+                .or_else(|| value.as_f64().map(|v| v as i64))
+                .map(Self::Counter),
+            SessionStatusKind::Flag => value
+                .as_bool()
+                .or_else(|| value.as_i64().map(|v| v != 0))
+                .map(Self::Flag),
+        }
+    }
+}
+
+/// Result of `core.get_session_status`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStatus(pub HashMap<SessionStatusKey, SessionStatusValue>);
+
+impl SessionStatus {
+    #[must_use]
+    pub fn get(&self, key: &SessionStatusKey) -> Option<&SessionStatusValue> {
+        self.0.get(key)
+    }
+
+    /// `None` if the key is missing or isn't a rate.
+    #[must_use]
+    pub fn rate(&self, key: &SessionStatusKey) -> Option<f64> {
+        match self.get(key)? {
+            SessionStatusValue::Rate(rate) => Some(*rate),
+            _ => None,
+        }
+    }
+
+    /// `None` if the key is missing or isn't a counter.
+    #[must_use]
+    pub fn counter(&self, key: &SessionStatusKey) -> Option<i64> {
+        match self.get(key)? {
+            SessionStatusValue::Counter(counter) => Some(*counter),
+            _ => None,
+        }
+    }
+
+    /// `None` if the key is missing or isn't a flag.
+    #[must_use]
+    pub fn flag(&self, key: &SessionStatusKey) -> Option<bool> {
+        match self.get(key)? {
+            SessionStatusValue::Flag(flag) => Some(*flag),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SessionStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Self(
+            HashMap::<String, Value>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = SessionStatusKey::new(&key);
+                    let value = SessionStatusValue::parse(&key, value);
+                    (key, value)
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::{json, Value};
+
+    use super::{SessionStatus, SessionStatusKey, SessionStatusValue};
+
+    #[test]
+    fn session_status() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"payload_download_rate": 1024, "dht_nodes": 312,
+            "has_incoming_connections": 1, "disk.disk_blocks_in_use": 20.0,
+            "net.recv_redundant_bytes": 5, "upload_rate": null, "net.ratio": 0.5}"#;
+        let status: SessionStatus = serde_json::from_str(json)?;
+        assert_eq!(
+            status.rate(&SessionStatusKey::PayloadDownloadRate),
+            Some(1024.0)
+        );
+        assert_eq!(status.counter(&SessionStatusKey::DhtNodes), Some(312));
+        assert_eq!(
+            status.flag(&SessionStatusKey::HasIncomingConnections),
+            Some(true)
+        );
+        assert_eq!(status.counter(&SessionStatusKey::DiskBlocksInUse), Some(20));
+        assert_eq!(
+            status.counter(&SessionStatusKey::new("net.recv_redundant_bytes")),
+            Some(5)
+        );
+        assert_eq!(
+            status.get(&SessionStatusKey::UploadRate),
+            Some(&SessionStatusValue::Raw(Value::Null))
+        );
+        assert_eq!(
+            status.get(&SessionStatusKey::new("net.ratio")),
+            Some(&SessionStatusValue::Raw(json!(0.5)))
+        );
+        assert_eq!(
+            SessionStatusKey::new("num_peers"),
+            SessionStatusKey::NumPeers
+        );
+        assert_eq!(
+            SessionStatusKey::new("net.recv_redundant_bytes").as_str(),
+            "net.recv_redundant_bytes"
+        );
+        assert_eq!(
+            json!([SessionStatusKey::DiskQueuedJobs, SessionStatusKey::NumPeers]),
+            json!(["disk.queued_disk_jobs", "num_peers"])
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use serde::{de, Deserialize};
+use serde_json::Value;
+
+/// Sample of a [`TimeSeries`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DataPoint {
+    /// Unix timestamp in seconds.
+    pub timestamp: f64,
+    pub value: f64,
+}
+
+/// Series of the Stats plugin, oldest sample first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeSeries(pub Vec<DataPoint>);
+
+impl TimeSeries {
+    #[must_use]
+    pub fn latest(&self) -> Option<&DataPoint> {
+        self.0.last()
+    }
+}
+
+/// Result of `stats.get_stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// Unix timestamp in seconds of the newest sample.
+    pub last_update: f64,
+    /// Seconds between samples.
+    pub interval: u64,
+    /// Max number of samples the plugin keeps.
+    pub length: usize,
+    /// Series by key, e.g. `upload_rate`.
+    pub series: HashMap<String, TimeSeries>,
+}
+
+impl<'de> Deserialize<'de> for Stats {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut map = HashMap::<String, Value>::deserialize(deserializer)?;
+        let mut take =
+            |key: &'static str| map.remove(key).ok_or_else(|| de::Error::missing_field(key));
+        let last_update: f64 =
+            serde_json::from_value(take("_last_update")?).map_err(de::Error::custom)?;
+        let interval: u64 =
+            serde_json::from_value(take("_update_interval")?).map_err(de::Error::custom)?;
+        let length = serde_json::from_value(take("_length")?).map_err(de::Error::custom)?;
+        #[allow(clippy::cast_precision_loss)]
+        let step = interval as f64;
+        let series = map
+            .into_iter()
+            .map(|(key, values)| {
+                let values: Vec<f64> = serde_json::from_value(values).map_err(de::Error::custom)?;
+                // The plugin sends the newest sample first
+                #[allow(clippy::cast_precision_loss)]
+                let points = values
+                    .into_iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(age, value)| DataPoint {
+                        timestamp: (age as f64).mul_add(-step, last_update),
+                        value,
+                    })
+                    .collect();
+                Ok((key, TimeSeries(points)))
+            })
+            .collect::<Result<_, D::Error>>()?;
+        Ok(Self {
+            last_update,
+            interval,
+            length,
+            series,
+        })
+    }
+}
+
+/// Result of `stats.get_totals` and `stats.get_session_totals`, in bytes.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatsTotals {
+    pub total_upload: u64,
+    pub total_download: u64,
+    pub total_payload_upload: u64,
+    pub total_payload_download: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::{DataPoint, Stats};
+
+    #[test]
+    fn stats() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"upload_rate": [30.0, 20.0, 10.0], "_last_update": 1000.0,
+            "_length": 150, "_update_interval": 5}"#;
+        let stats: Stats = serde_json::from_str(json)?;
+        assert_eq!(stats.interval, 5);
+        assert_eq!(stats.length, 150);
+        let series = &stats.series["upload_rate"];
+        assert_eq!(
+            series.0[0],
+            DataPoint {
+                timestamp: 990.0,
+                value: 10.0
+            }
+        );
+        assert_eq!(
+            series.latest(),
+            Some(&DataPoint {
+                timestamp: 1000.0,
+                value: 30.0
+            })
+        );
+        Ok(())
+    }
+}
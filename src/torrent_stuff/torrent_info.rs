@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, HashMap};
+
+use camino::Utf8PathBuf;
+use serde::Deserialize;
+
+use super::TorrentId;
+
+/// Result of `web.get_magnet_info`.
+#[derive(Deserialize, Debug)]
+pub struct MagnetInfo {
+    pub name: String,
+    pub info_hash: TorrentId,
+    /// Tracker url to tier.
+    #[serde(default)]
+    pub trackers: HashMap<String, usize>,
+}
+
+/// Result of `web.get_torrent_info`.
+#[derive(Deserialize, Debug)]
+pub struct TorrentInfo {
+    pub name: String,
+    pub info_hash: TorrentId,
+    pub files_tree: FileTree,
+}
+
+impl TorrentInfo {
+    /// Files of the torrent sorted by their index.
+    #[must_use]
+    pub fn files(&self) -> Vec<TorrentFile> {
+        let mut files = Vec::new();
+        walk(&self.files_tree.contents, &Utf8PathBuf::new(), &mut files);
+        files.sort_by_key(|file| file.index);
+        files
+    }
+
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.files_tree
+            .contents
+            .values()
+            .map(FileTreeNode::length)
+            .sum()
+    }
+}
+
+fn walk(
+    contents: &BTreeMap<String, FileTreeNode>,
+    parent: &Utf8PathBuf,
+    out: &mut Vec<TorrentFile>,
+) {
+    for (name, node) in contents {
+        let path = parent.join(name);
+        match node {
+            FileTreeNode::File { index, length, .. } => out.push(TorrentFile {
+                index: *index,
+                path,
+                length: *length,
+            }),
+            FileTreeNode::Dir { contents, .. } => walk(contents, &path, out),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FileTree {
+    pub contents: BTreeMap<String, FileTreeNode>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum FileTreeNode {
+    File {
+        index: usize,
+        length: u64,
+        #[serde(default)]
+        download: bool,
+    },
+    Dir {
+        contents: BTreeMap<String, Self>,
+        #[serde(default)]
+        length: u64,
+        #[serde(default)]
+        download: bool,
+    },
+}
+
+impl FileTreeNode {
+    #[must_use]
+    pub fn length(&self) -> u64 {
+        match self {
+            Self::File { length, .. } => *length,
+            Self::Dir { contents, .. } => contents.values().map(Self::length).sum(),
+        }
+    }
+}
+
+/// Flattened file entry of a [`TorrentInfo`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TorrentFile {
+    pub index: usize,
+    pub path: Utf8PathBuf,
+    pub length: u64,
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use super::TorrentInfo;
+
+    #[test]
+    fn files_tree() -> Result<(), Box<dyn Error>> {
+        let json = r#"{
+            "name": "dir",
+            "info_hash": "c9e15763f722f23e98a29decdfae341b98d53056",
+            "files_tree": {"contents": {"dir": {"type": "dir", "length": 30, "download": true, "contents": {
+                "b.txt": {"type": "file", "index": 1, "length": 20, "download": true, "path": "dir/b.txt"},
+                "a.txt": {"type": "file", "index": 0, "length": 10, "download": true, "path": "dir/a.txt"}
+            }}}}
+        }"#;
+        let info: TorrentInfo = serde_json::from_str(json)?;
+        assert_eq!(info.total_size(), 30);
+        let files = info.files();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].index, 0);
+        assert_eq!(files[0].path, "dir/a.txt");
+        assert_eq!(files[1].path, "dir/b.txt");
+        assert_eq!(files[1].length, 20);
+        Ok(())
+    }
+}
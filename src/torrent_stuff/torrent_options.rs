@@ -2,13 +2,18 @@ use std::collections::HashMap;
 
 use camino::Utf8PathBuf;
 use derivative::Derivative;
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde::{de, Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::enum_map::{EnumMap, SerializableEnum};
+use crate::{
+    enum_map::{serializable_enum, DeserializableEnum, EnumMap},
+    error::InvalidTorrentOption,
+};
+
+use super::RemotePath;
 
 // Every value **must** fit in 7bits, as it's cast as an i8.
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum TorrentPriorities {
     Skip = 0,
@@ -27,99 +32,323 @@ impl Serialize for TorrentPriorities {
     }
 }
 
+/// libtorrent priorities go from 0 to 7, the ones between the variants are rounded down.
+impl<'de> Deserialize<'de> for TorrentPriorities {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(Self::Skip),
+            1..=3 => Ok(Self::Low),
+            4..=6 => Ok(Self::Normal),
+            7 => Ok(Self::High),
+            other => Err(de::Error::custom(format!("invalid priority {other}"))),
+        }
+    }
+}
+
 pub type TorrentOptions = EnumMap<TorrentOption>;
 
-#[derive(Derivative, Debug, strum::Display)]
-#[strum(serialize_all = "snake_case")]
-pub enum TorrentOption {
-    AddPaused(bool),
-    AutoManaged(bool),
-    DownloadLocation(Utf8PathBuf),
-    FilePriorities(Vec<TorrentPriorities>),
-    MappedFiles(HashMap<i32, Utf8PathBuf>),
-    MaxConnections(i64),
-    MaxDownloadSpeed(f64),
-    MaxUploadSlots(i64),
-    MaxUploadSpeed(f64),
-    MoveCompleted(bool),
-    MoveCompletedPath(Utf8PathBuf),
-    Name(String),
-    Owner(String),
-    PreAllocateStorage(bool),
-    PrioritizeFirstLastPieces(bool),
-    RemoveAtRatio(bool),
-    SeedMode(bool),
-    SequentialDownload(bool),
-    Shared(bool),
-    StopAtRatio(bool),
-    StopRatio(f64),
-    SuperSeeding(bool),
+serializable_enum! {
+    /// Options of Deluge's `TorrentOptions`, `-1` means unlimited for limits and speeds.
+    #[derive(Derivative, Debug, Clone, PartialEq, strum::Display)]
+    #[strum(serialize_all = "snake_case")]
+    pub enum TorrentOption: TorrentOptionKind {
+        /// Only used when adding.
+        AddPaused(bool) => add_paused,
+        AutoManaged(bool) => auto_managed,
+        DownloadLocation(RemotePath) => download_location,
+        /// One per file, in file index order.
+        FilePriorities(Vec<TorrentPriorities>) => file_priorities,
+        /// File index to its new path, only used when adding.
+        MappedFiles(HashMap<i32, Utf8PathBuf>) => mapped_files,
+        MaxConnections(i64) => max_connections,
+        /// In KiB/s.
+        MaxDownloadSpeed(f64) => max_download_speed,
+        MaxUploadSlots(i64) => max_upload_slots,
+        /// In KiB/s.
+        MaxUploadSpeed(f64) => max_upload_speed,
+        /// Needs [`TorrentOption::MoveCompletedPath`].
+        MoveCompleted(bool) => move_completed,
+        MoveCompletedPath(RemotePath) => move_completed_path,
+        Name(String) => name,
+        Owner(String) => owner,
+        /// Full allocation instead of sparse files (libtorrent's `storage_mode`), only used when
+        /// adding.
+        PreAllocateStorage(bool) => pre_allocate_storage,
+        PrioritizeFirstLastPieces(bool) => prioritize_first_last_pieces,
+        /// Removes the torrent instead of pausing it, needs [`TorrentOption::StopAtRatio`].
+        RemoveAtRatio(bool) => remove_at_ratio,
+        /// Skips checking the files, only used when adding.
+        SeedMode(bool) => seed_mode,
+        SequentialDownload(bool) => sequential_download,
+        Shared(bool) => shared,
+        StopAtRatio(bool) => stop_at_ratio,
+        StopRatio(f64) => stop_ratio,
+        SuperSeeding(bool) => super_seeding,
+    }
 }
 
 impl TorrentOption {
-    // Look into more generic, shorter way to handle this
-    fn get_inner(&self) -> Value {
-        match self {
-            TorrentOption::DownloadLocation(value) | TorrentOption::MoveCompletedPath(value) => {
-                json!(value)
-            }
-            TorrentOption::Name(value) | TorrentOption::Owner(value) => json!(value),
-            TorrentOption::FilePriorities(value) => json!(value),
-            TorrentOption::MappedFiles(value) => json!(value),
-            TorrentOption::MaxConnections(value) | TorrentOption::MaxUploadSlots(value) => {
-                json!(value)
+    /// Options that are part of a torrent's status, the others only apply when adding it.
+    /// The status calls [`TorrentOption::PrioritizeFirstLastPieces`] `prioritize_first_last`.
+    pub(crate) const STATUS_KEYS: [&'static str; 18] = [
+        "auto_managed",
+        "download_location",
+        "file_priorities",
+        "max_connections",
+        "max_download_speed",
+        "max_upload_slots",
+        "max_upload_speed",
+        "move_completed",
+        "move_completed_path",
+        "name",
+        "owner",
+        "prioritize_first_last",
+        "remove_at_ratio",
+        "sequential_download",
+        "shared",
+        "stop_at_ratio",
+        "stop_ratio",
+        "super_seeding",
+    ];
+}
+
+impl TorrentOptions {
+    /// Checks the options don't contradict each other. `file_count` is the number of files of
+    /// the torrent, if known, to check [`TorrentOption::FilePriorities`] and
+    /// [`TorrentOption::MappedFiles`] against.
+    pub fn validate(&self, file_count: Option<usize>) -> Result<(), InvalidTorrentOption> {
+        let mut move_completed = false;
+        let mut has_move_completed_path = false;
+        let mut remove_at_ratio = false;
+        let mut stop_at_ratio = None;
+        for option in self {
+            match option {
+                TorrentOption::MoveCompleted(value) => move_completed = *value,
+                TorrentOption::MoveCompletedPath(path) => {
+                    has_move_completed_path = !path.as_str().is_empty();
+                }
+                TorrentOption::RemoveAtRatio(value) => remove_at_ratio = *value,
+                TorrentOption::StopAtRatio(value) => stop_at_ratio = Some(*value),
+                TorrentOption::StopRatio(ratio) if *ratio < 0.0 => {
+                    return Err(InvalidTorrentOption::NegativeStopRatio(*ratio));
+                }
+                TorrentOption::MaxDownloadSpeed(speed) | TorrentOption::MaxUploadSpeed(speed)
+                    if *speed < 0.0 && (*speed - -1.0).abs() > f64::EPSILON =>
+                {
+                    return Err(InvalidTorrentOption::InvalidLimit(option.to_string()));
+                }
+                TorrentOption::MaxConnections(limit) | TorrentOption::MaxUploadSlots(limit)
+                    if *limit < -1 =>
+                {
+                    return Err(InvalidTorrentOption::InvalidLimit(option.to_string()));
+                }
+                TorrentOption::Name(name) if name.is_empty() => {
+                    return Err(InvalidTorrentOption::EmptyName);
+                }
+                TorrentOption::FilePriorities(priorities) => {
+                    if let Some(file_count) = file_count.filter(|&n| n != priorities.len()) {
+                        return Err(InvalidTorrentOption::FilePrioritiesLength {
+                            expected: file_count,
+                            found: priorities.len(),
+                        });
+                    }
+                }
+                TorrentOption::MappedFiles(files) => {
+                    if let Some(&index) = files.keys().find(|&&index| {
+                        usize::try_from(index).map_or(true, |index| {
+                            file_count.is_some_and(|file_count| index >= file_count)
+                        })
+                    }) {
+                        return Err(InvalidTorrentOption::MappedFileIndex(index));
+                    }
+                }
+                _ => {}
             }
-            TorrentOption::AddPaused(value)
-            | TorrentOption::AutoManaged(value)
-            | TorrentOption::MoveCompleted(value)
-            | TorrentOption::PreAllocateStorage(value)
-            | TorrentOption::PrioritizeFirstLastPieces(value)
-            | TorrentOption::RemoveAtRatio(value)
-            | TorrentOption::SeedMode(value)
-            | TorrentOption::SequentialDownload(value)
-            | TorrentOption::Shared(value)
-            | TorrentOption::StopAtRatio(value)
-            | TorrentOption::SuperSeeding(value) => json!(value),
-            TorrentOption::MaxDownloadSpeed(value)
-            | TorrentOption::MaxUploadSpeed(value)
-            | TorrentOption::StopRatio(value) => json!(value),
         }
-    }
-    fn get_name(&self) -> String {
-        self.to_string()
+        if move_completed && !has_move_completed_path {
+            return Err(InvalidTorrentOption::MoveCompletedWithoutPath);
+        }
+        if remove_at_ratio && stop_at_ratio == Some(false) {
+            return Err(InvalidTorrentOption::RemoveAtRatioWithoutStop);
+        }
+        Ok(())
     }
 }
 
-impl SerializableEnum for TorrentOption {
-    type K = String;
-
-    type V = Value;
-
-    fn get_key(&self) -> Self::K {
-        self.get_name()
-    }
-
-    fn get_value(&self) -> Self::V {
-        self.get_inner()
+/// Single entry map, e.g. `{"max_connections": 50}`.
+impl<'de> Deserialize<'de> for TorrentOption {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let map = HashMap::<String, Value>::deserialize(deserializer)?;
+        let len = map.len();
+        let mut entries = map.into_iter();
+        match (entries.next(), entries.next()) {
+            (Some((key, value)), None) => Self::from_key_value(&key, value)
+                .ok_or_else(|| de::Error::custom(format!("unknown torrent option {key}")))?
+                .map_err(de::Error::custom),
+            _ => Err(de::Error::invalid_length(len, &"a single option")),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use camino::Utf8PathBuf;
+    use std::collections::HashMap;
+
     use serde_json::json;
 
-    use super::{TorrentOption, TorrentOptions};
+    use super::{TorrentOption, TorrentOptionKind, TorrentOptions, TorrentPriorities};
+    use crate::{
+        error::InvalidTorrentOption,
+        torrent_stuff::{StatusOptions, TorrentId},
+    };
+
+    #[test]
+    fn round_trip() -> Result<(), serde_json::Error> {
+        let json = json!({
+            "max_connections": 50,
+            "file_priorities": [0, 1, 4, 7, 5],
+            "mapped_files": {"0": "a/b.txt"},
+            "storage_mode": "sparse"
+        });
+        let options: TorrentOptions = serde_json::from_value(json.clone())?;
+        assert_eq!(options.len(), 3);
+        assert_eq!(options.unknown()["storage_mode"], "sparse");
+        assert!(matches!(
+            options.get(TorrentOptionKind::MaxConnections),
+            Some(TorrentOption::MaxConnections(50))
+        ));
+        let mut expected = json;
+        expected["file_priorities"] = json!([0, 1, 4, 7, 4]);
+        assert_eq!(json!(options), expected);
+
+        let option: TorrentOption = serde_json::from_str(r#"{"stop_ratio": 2}"#)?;
+        assert!(
+            matches!(option, TorrentOption::StopRatio(ratio) if (ratio - 2.0).abs() < f64::EPSILON)
+        );
+        let error = serde_json::from_str::<TorrentOption>(r#"{"stop_ratio": 2, "shared": true}"#)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("invalid length 2"));
+        let error = serde_json::from_str::<TorrentOption>("{}").unwrap_err();
+        assert!(error.to_string().starts_with("invalid length 0"));
+        assert!(serde_json::from_str::<TorrentOptions>(r#"{"max_connections": "a"}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn status_options() -> Result<(), Box<dyn std::error::Error>> {
+        // core.get_torrents_status({"id": [..]}, TorrentOption::STATUS_KEYS)
+        let status = json!({
+            "0a8c1a1dd8a3b3f4e6a27fbe4d1c3f0e2b5a9d71": {
+                "auto_managed": true,
+                "download_location": "/downloads",
+                "file_priorities": [4, 4, 0],
+                "max_connections": -1,
+                "max_download_speed": -1,
+                "max_upload_slots": -1,
+                "max_upload_speed": -1.0,
+                "move_completed": false,
+                "move_completed_path": "/downloads/done",
+                "name": "ubuntu-24.04-desktop-amd64.iso",
+                "owner": "localclient",
+                "prioritize_first_last": true,
+                "remove_at_ratio": false,
+                "sequential_download": false,
+                "shared": false,
+                "stop_at_ratio": false,
+                "stop_ratio": 2.0,
+                "super_seeding": false
+            }
+        });
+        let status: HashMap<TorrentId, StatusOptions> = serde_json::from_value(status)?;
+        let options = &status[&"0a8c1a1dd8a3b3f4e6a27fbe4d1c3f0e2b5a9d71".parse()?].0;
+        assert_eq!(options.len(), TorrentOption::STATUS_KEYS.len());
+        assert!(options.unknown().is_empty());
+        assert!(matches!(
+            options.get(TorrentOptionKind::PrioritizeFirstLastPieces),
+            Some(TorrentOption::PrioritizeFirstLastPieces(true))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn validate() {
+        let options = |items: Vec<TorrentOption>| {
+            let mut options = TorrentOptions::new();
+            options.extend(items);
+            options
+        };
+        assert_eq!(
+            options(vec![TorrentOption::MoveCompleted(true)]).validate(None),
+            Err(InvalidTorrentOption::MoveCompletedWithoutPath)
+        );
+        assert!(options(vec![
+            TorrentOption::MoveCompleted(true),
+            TorrentOption::MoveCompletedPath("/done".into()),
+        ])
+        .validate(None)
+        .is_ok());
+        assert_eq!(
+            options(vec![TorrentOption::StopRatio(-1.0)]).validate(None),
+            Err(InvalidTorrentOption::NegativeStopRatio(-1.0))
+        );
+        assert_eq!(
+            options(vec![
+                TorrentOption::RemoveAtRatio(true),
+                TorrentOption::StopAtRatio(false),
+            ])
+            .validate(None),
+            Err(InvalidTorrentOption::RemoveAtRatioWithoutStop)
+        );
+        let priorities = options(vec![TorrentOption::FilePriorities(vec![
+            TorrentPriorities::Normal;
+            2
+        ])]);
+        assert!(priorities.validate(None).is_ok());
+        assert_eq!(
+            priorities.validate(Some(3)),
+            Err(InvalidTorrentOption::FilePrioritiesLength {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            options(vec![TorrentOption::MappedFiles(HashMap::from([(
+                -1,
+                "a".into()
+            )]))])
+            .validate(None),
+            Err(InvalidTorrentOption::MappedFileIndex(-1))
+        );
+        assert!(options(vec![
+            TorrentOption::MaxUploadSpeed(-1.0),
+            TorrentOption::MaxConnections(-1),
+        ])
+        .validate(None)
+        .is_ok());
+        assert_eq!(
+            options(vec![TorrentOption::MaxDownloadSpeed(-2.0)]).validate(None),
+            Err(InvalidTorrentOption::InvalidLimit(
+                "max_download_speed".into()
+            ))
+        );
+    }
 
     #[test]
     fn test() {
         let mut options = TorrentOptions::new();
         options.insert(TorrentOption::MaxConnections(32));
         options.insert(TorrentOption::MaxConnections(31));
-        options.insert(TorrentOption::MoveCompletedPath(Utf8PathBuf::from("path")));
+        options.insert(TorrentOption::MoveCompletedPath("path".into()));
+        options.insert(TorrentOption::AutoManaged(true));
 
         assert_eq!(
-            r#"{"max_connections":31,"move_completed_path":"path"}"#,
+            r#"{"auto_managed":true,"max_connections":31,"move_completed_path":"path"}"#,
             format!("{}", json!(options))
         );
     }
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use serde::{de, de::DeserializeOwned, ser, Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::enum_map::{DeserializableEnum, SerializableEnum};
+
+use super::{RemotePath, TorrentOption, TorrentOptions};
+
+/// Torrent options which AutoAdd applies only if their `<option>_toggle` is set, with the
+/// name AutoAdd uses for them.
+const TOGGLED_OPTIONS: [&str; 13] = [
+    "add_paused",
+    "auto_managed",
+    "download_location",
+    "max_connections",
+    "max_download_speed",
+    "max_upload_slots",
+    "max_upload_speed",
+    "move_completed",
+    "move_completed_path",
+    "prioritize_first_last",
+    "remove_at_ratio",
+    "stop_at_ratio",
+    "stop_ratio",
+];
+/// Torrent options which AutoAdd always applies.
+const UNTOGGLED_OPTIONS: [&str; 2] = ["owner", "seed_mode"];
+
+/// AutoAdd calls [`TorrentOption::PrioritizeFirstLastPieces`] `prioritize_first_last`.
+fn autoadd_key(option: &TorrentOption) -> String {
+    match option {
+        TorrentOption::PrioritizeFirstLastPieces(_) => "prioritize_first_last".to_owned(),
+        _ => option.get_key().to_owned(),
+    }
+}
+
+fn option_key(autoadd_key: &str) -> &str {
+    match autoadd_key {
+        "prioritize_first_last" => "prioritize_first_last_pieces",
+        _ => autoadd_key,
+    }
+}
+
+/// Watch folder of the AutoAdd plugin.
+#[derive(Debug, Default)]
+pub struct WatchDir {
+    /// Folder in the daemon's filesystem.
+    pub path: RemotePath,
+    pub enabled: bool,
+    /// Rename the torrent files to `<name><append_extension>` after adding them.
+    pub append_extension: Option<String>,
+    /// Copy the torrent files to this folder after adding them.
+    pub copy_torrent: Option<RemotePath>,
+    /// Delete the copy of the torrent file when the torrent is removed.
+    pub delete_copy_torrent: bool,
+    /// Needs the Label plugin.
+    pub label: Option<String>,
+    /// `Some(true)` queues to the top, `Some(false)` to the bottom.
+    pub queue_to_top: Option<bool>,
+    /// Options applied to the added torrents. Only `AddPaused`, `AutoManaged`,
+    /// `DownloadLocation`, `MaxConnections`, `MaxDownloadSpeed`, `MaxUploadSlots`,
+    /// `MaxUploadSpeed`, `MoveCompleted`, `MoveCompletedPath`, `Owner`,
+    /// `PrioritizeFirstLastPieces`, `RemoveAtRatio`, `SeedMode`, `StopAtRatio` and
+    /// `StopRatio` are supported.
+    pub options: TorrentOptions,
+}
+
+impl WatchDir {
+    #[must_use]
+    pub fn new(path: RemotePath) -> Self {
+        Self {
+            path,
+            ..Self::default()
+        }
+    }
+}
+
+impl Serialize for WatchDir {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = Map::new();
+        map.insert("path".to_owned(), json!(self.path));
+        map.insert("enabled".to_owned(), json!(self.enabled));
+        for (key, value) in [
+            (
+                "append_extension",
+                self.append_extension.as_ref().map(|v| json!(v)),
+            ),
+            ("copy_torrent", self.copy_torrent.as_ref().map(|v| json!(v))),
+            ("label", self.label.as_ref().map(|v| json!(v))),
+            ("queue_to_top", self.queue_to_top.map(|v| json!(v))),
+        ] {
+            map.insert(format!("{key}_toggle"), json!(value.is_some()));
+            if let Some(value) = value {
+                map.insert(key.to_owned(), value);
+            }
+        }
+        map.insert(
+            "delete_copy_torrent_toggle".to_owned(),
+            json!(self.delete_copy_torrent),
+        );
+        for key in TOGGLED_OPTIONS {
+            map.insert(format!("{key}_toggle"), json!(false));
+        }
+        for option in &self.options {
+            let key = autoadd_key(option);
+            if TOGGLED_OPTIONS.contains(&key.as_str()) {
+                map.insert(format!("{key}_toggle"), json!(true));
+            } else if !UNTOGGLED_OPTIONS.contains(&key.as_str()) {
+                return Err(ser::Error::custom(format!(
+                    "option {key} isn't supported by AutoAdd"
+                )));
+            }
+            map.insert(key, option.get_value());
+        }
+        map.serialize(serializer)
+    }
+}
+
+fn take<T, E>(map: &mut HashMap<String, Value>, key: &str, enabled: bool) -> Result<Option<T>, E>
+where
+    T: DeserializeOwned,
+    E: de::Error,
+{
+    map.remove(key)
+        .filter(|_| enabled)
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(E::custom)
+}
+
+impl<'de> Deserialize<'de> for WatchDir {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut map = HashMap::<String, Value>::deserialize(deserializer)?;
+        // Missing toggles default to what AutoAdd does when they are missing
+        let toggle = |key: &str, default: bool| {
+            map.get(&format!("{key}_toggle"))
+                .and_then(Value::as_bool)
+                .unwrap_or(default)
+        };
+        let append_extension_toggle = toggle("append_extension", false);
+        let copy_torrent_toggle = toggle("copy_torrent", false);
+        let label_toggle = toggle("label", true);
+        let queue_to_top_toggle = toggle("queue_to_top", true);
+        let delete_copy_torrent = toggle("delete_copy_torrent", false);
+        let option_keys = TOGGLED_OPTIONS
+            .into_iter()
+            .filter(|key| toggle(key, true))
+            .chain(UNTOGGLED_OPTIONS)
+            .collect::<Vec<_>>();
+
+        let mut options = TorrentOptions::new();
+        for key in option_keys {
+            if let Some(option) = map
+                .remove(key)
+                .and_then(|value| TorrentOption::from_key_value(option_key(key), value))
+            {
+                options.insert(option.map_err(de::Error::custom)?);
+            }
+        }
+        Ok(Self {
+            path: take(&mut map, "path", true)?.ok_or_else(|| de::Error::missing_field("path"))?,
+            enabled: take(&mut map, "enabled", true)?.unwrap_or_default(),
+            append_extension: take(&mut map, "append_extension", append_extension_toggle)?,
+            copy_torrent: take(&mut map, "copy_torrent", copy_torrent_toggle)?,
+            delete_copy_torrent,
+            label: take::<String, _>(&mut map, "label", label_toggle)?
+                .filter(|label| !label.is_empty()),
+            queue_to_top: take(&mut map, "queue_to_top", queue_to_top_toggle)?,
+            options,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::WatchDir;
+    use crate::torrent_stuff::TorrentOption;
+
+    #[test]
+    fn watch_dir() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"path": "/watch/tv", "abspath": "/watch/tv", "enabled": true,
+            "append_extension_toggle": false, "append_extension": ".added",
+            "label_toggle": true, "label": "tv",
+            "download_location_toggle": true, "download_location": "/data/tv",
+            "max_connections_toggle": false, "max_connections": 50,
+            "prioritize_first_last_toggle": true, "prioritize_first_last": true,
+            "owner": "localclient"}"#;
+        let dir: WatchDir = serde_json::from_str(json)?;
+        assert_eq!(dir.path, "/watch/tv");
+        assert!(dir.enabled);
+        assert_eq!(dir.append_extension, None);
+        assert_eq!(dir.label.as_deref(), Some("tv"));
+        assert_eq!(dir.options.len(), 3);
+
+        let value = json!(dir);
+        assert_eq!(value["download_location"], json!("/data/tv"));
+        assert_eq!(value["download_location_toggle"], json!(true));
+        assert_eq!(value["prioritize_first_last"], json!(true));
+        assert_eq!(value["max_connections_toggle"], json!(false));
+        assert_eq!(value["owner"], json!("localclient"));
+        assert_eq!(value["append_extension_toggle"], json!(false));
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_option() {
+        let mut dir = WatchDir::new("/watch".into());
+        dir.options.insert(TorrentOption::SequentialDownload(true));
+        assert!(serde_json::to_value(&dir).is_err());
+    }
+}
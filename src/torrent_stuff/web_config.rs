@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Configuration of deluge-web (`web.conf`).
+///
+/// Every field is optional so the same type can be used for partial updates through
+/// `web.set_config`, only the fields that are `Some` are sent.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct WebConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub https: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkey: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert: Option<String>,
+    /// Base path deluge-web is served under, e.g. `/deluge`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// In seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_timeout: Option<u64>,
+    /// Host id of the daemon to connect to on login.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_daemon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_plugins: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_login: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_session_speed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_sidebar: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidebar_show_zero: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidebar_multiple_filters: Option<bool>,
+    /// Keys not modeled above.
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use serde_json::json;
+
+    use super::WebConfig;
+
+    #[test]
+    fn web_config() -> Result<(), Box<dyn Error>> {
+        let json = r#"{"port": 8112, "https": false, "session_timeout": 3600, "theme": "gray",
+            "base": "/", "default_daemon": "", "show_keyword_search": false}"#;
+        let config: WebConfig = serde_json::from_str(json)?;
+        assert_eq!(config.port, Some(8112));
+        assert_eq!(config.session_timeout, Some(3600));
+        assert_eq!(config.other["show_keyword_search"], json!(false));
+
+        let update = WebConfig {
+            session_timeout: Some(600),
+            ..WebConfig::default()
+        };
+        assert_eq!(r#"{"session_timeout":600}"#, format!("{}", json!(update)));
+        Ok(())
+    }
+}
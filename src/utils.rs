@@ -1,2 +1,22 @@
+use serde::Deserialize;
+
+#[allow(dead_code)]
 #[derive(Debug)]
-pub enum Never{}
\ No newline at end of file
+pub enum Never {}
+
+/// Some methods of deluge-web return `false` instead of a value on failure.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum OrFalse<T> {
+    Value(T),
+    False(#[allow(dead_code)] bool),
+}
+
+impl<T> OrFalse<T> {
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Self::Value(value) => Some(value),
+            Self::False(_) => None,
+        }
+    }
+}